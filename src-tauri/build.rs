@@ -11,7 +11,14 @@ use std::path::Path;
 use tar::Archive;
 
 fn main() {
-    tauri::async_runtime::block_on(setup_binary()).unwrap();
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create runtime")
+        .block_on(setup_binary())
+        .unwrap();
+    // The headless CLI is built without tauri
+    #[cfg(feature = "gui")]
     tauri_build::build()
 }
 
@@ -0,0 +1,259 @@
+use crate::{
+    error::ManagerError,
+    manager::NodeManager,
+    utils::{get_node_ports, is_port_in_use},
+};
+use eyre::{bail, eyre, Result};
+use reqwest::{Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Application installed on a node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Application {
+    pub id: String,
+    pub blob: String,
+    pub size: u64,
+    pub source: String,
+    #[serde(default)]
+    pub metadata: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Context {
+    pub id: String,
+    pub application_id: String,
+    #[serde(default)]
+    pub root_hash: Option<String>,
+}
+
+// The context a node created or joined and the identity it is a member with
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMembership {
+    pub context_id: String,
+    pub member_public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextIdentity {
+    pub public_key: String,
+    pub private_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdminHealth {
+    pub status: String,
+}
+
+// Successful responses of the admin API wrap their payload in `data`
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct ApplicationList {
+    apps: Vec<Application>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledApplication {
+    application_id: String,
+}
+
+#[derive(Deserialize)]
+struct ContextList {
+    contexts: Vec<Context>,
+}
+
+#[derive(Deserialize)]
+struct IdentityList {
+    identities: Vec<String>,
+}
+
+// Client for the admin HTTP API a node serves on its server port. It uses the
+// unauthenticated dev routes meroctl uses, which are only served on the node's
+// listen address.
+#[derive(Clone)]
+pub struct AdminClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl AdminClient {
+    // base_url is the node's server address, e.g. http://127.0.0.1:2428
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| eyre!("Failed to create HTTP client: {}", e))?;
+        Ok(AdminClient {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        })
+    }
+
+    // Client for a managed node, which must be serving on its server port
+    pub fn for_node(manager: &NodeManager, node_name: &str) -> Result<Self> {
+        let config = get_node_ports(node_name, manager)?;
+        if !is_port_in_use(config.server_port) {
+            bail!(ManagerError::NodeNotRunning {
+                node_name: node_name.to_string()
+            });
+        }
+        AdminClient::new(format!("http://127.0.0.1:{}", config.server_port))
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}/admin-api{}", self.base_url, path))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| eyre!("Failed to reach admin API: {}", e))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| eyre!("Failed to read admin API response: {}", e))?;
+        if !status.is_success() {
+            bail!("Admin API request failed with {}: {}", status, body.trim());
+        }
+        let envelope: Envelope<T> = serde_json::from_str(&body)
+            .map_err(|e| eyre!("Failed to parse admin API response: {}", e))?;
+        Ok(envelope.data)
+    }
+
+    pub async fn health(&self) -> Result<AdminHealth> {
+        self.send(self.request(Method::GET, "/health")).await
+    }
+
+    pub async fn list_applications(&self) -> Result<Vec<Application>> {
+        let list: ApplicationList = self
+            .send(self.request(Method::GET, "/dev/applications"))
+            .await?;
+        Ok(list.apps)
+    }
+
+    // Install an application from a URL, verified against the hash if given.
+    // Returns the application id.
+    pub async fn install_application(
+        &self,
+        url: String,
+        hash: Option<String>,
+        metadata: Vec<u8>,
+    ) -> Result<String> {
+        let body = json!({ "url": url, "hash": hash, "metadata": metadata });
+        let installed: InstalledApplication = self
+            .send(
+                self.request(Method::POST, "/dev/install-application")
+                    .json(&body),
+            )
+            .await?;
+        Ok(installed.application_id)
+    }
+
+    // Install an application from a wasm file on the node's machine
+    pub async fn install_dev_application(
+        &self,
+        path: PathBuf,
+        metadata: Vec<u8>,
+    ) -> Result<String> {
+        let body = json!({ "path": path, "metadata": metadata });
+        let installed: InstalledApplication = self
+            .send(
+                self.request(Method::POST, "/dev/install-dev-application")
+                    .json(&body),
+            )
+            .await?;
+        Ok(installed.application_id)
+    }
+
+    pub async fn list_contexts(&self) -> Result<Vec<Context>> {
+        let list: ContextList = self
+            .send(self.request(Method::GET, "/dev/contexts"))
+            .await?;
+        Ok(list.contexts)
+    }
+
+    pub async fn create_context(
+        &self,
+        application_id: String,
+        initialization_params: Vec<u8>,
+    ) -> Result<ContextMembership> {
+        let body = json!({
+            "applicationId": application_id,
+            "initializationParams": initialization_params,
+        });
+        self.send(self.request(Method::POST, "/dev/contexts").json(&body))
+            .await
+    }
+
+    pub async fn delete_context(&self, context_id: &str) -> Result<()> {
+        let _: Value = self
+            .send(self.request(Method::DELETE, &format!("/dev/contexts/{}", context_id)))
+            .await?;
+        Ok(())
+    }
+
+    // Invite another identity to a context, returns the invitation payload the invitee joins with
+    pub async fn invite_to_context(
+        &self,
+        context_id: String,
+        inviter_id: String,
+        invitee_id: String,
+    ) -> Result<String> {
+        let body = json!({
+            "contextId": context_id,
+            "inviterId": inviter_id,
+            "inviteeId": invitee_id,
+        });
+        self.send(
+            self.request(Method::POST, "/dev/contexts/invite")
+                .json(&body),
+        )
+        .await
+    }
+
+    pub async fn join_context(
+        &self,
+        private_key: String,
+        invitation_payload: String,
+    ) -> Result<ContextMembership> {
+        let body = json!({
+            "privateKey": private_key,
+            "invitationPayload": invitation_payload,
+        });
+        self.send(self.request(Method::POST, "/dev/contexts/join").json(&body))
+            .await
+    }
+
+    // Public keys of the members of a context
+    pub async fn list_context_identities(&self, context_id: &str) -> Result<Vec<String>> {
+        let list: IdentityList = self
+            .send(self.request(
+                Method::GET,
+                &format!("/dev/contexts/{}/identities", context_id),
+            ))
+            .await?;
+        Ok(list.identities)
+    }
+
+    // Generate a key pair to join a context with
+    pub async fn generate_context_identity(&self) -> Result<ContextIdentity> {
+        self.send(self.request(Method::POST, "/dev/identity/context"))
+            .await
+    }
+}
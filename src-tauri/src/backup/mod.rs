@@ -0,0 +1,368 @@
+use crate::{
+    error::ManagerError,
+    logger::create_log_file,
+    manager::NodeManager,
+    operations::{start_node, stop_node_process, write_node_ports},
+    store::{get_node_settings, modify_settings},
+    types::{BackupFile, BackupManifest, NodeEvent, NodeProcess},
+    utils::{
+        get_assigned_ports, get_backups_dir, get_meroctl_version, get_node_ports, get_nodes_dir,
+        is_node_process_running, is_port_in_use, move_path, validate_node_name,
+    },
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tar::{Archive, Builder, Header};
+
+pub mod schedule;
+
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+// Directory inside the archive holding the node home directory
+const NODE_DIR: &str = "node";
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).map_err(|e| eyre!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|e| eyre!("Failed to read {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Relative paths of all regular files below a directory, using '/' as separator
+pub fn collect_files(root: &Path) -> Result<Vec<String>> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+        for entry in fs::read_dir(dir)
+            .map_err(|e| eyre!("Failed to read directory {}: {}", dir.display(), e))?
+        {
+            let entry = entry.map_err(|e| eyre!("Failed to read directory entry: {}", e))?;
+            let file_type = entry
+                .file_type()
+                .map_err(|e| eyre!("Failed to read file type: {}", e))?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                walk(root, &path, files)?;
+            } else if file_type.is_file() {
+                let relative = path
+                    .strip_prefix(root)
+                    .map_err(|e| eyre!("Failed to resolve relative path: {}", e))?;
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(relative);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+pub fn build_manifest_files(node_dir: &Path) -> Result<Vec<BackupFile>> {
+    collect_files(node_dir)?
+        .into_iter()
+        .map(|path| {
+            let full_path = node_dir.join(&path);
+            let size = fs::metadata(&full_path)
+                .map_err(|e| eyre!("Failed to read metadata of {}: {}", path, e))?
+                .len();
+            Ok(BackupFile {
+                sha256: sha256_file(&full_path)?,
+                path,
+                size,
+            })
+        })
+        .collect()
+}
+
+// Write the manifest followed by the node directory into a gzipped tarball
+pub fn write_archive(
+    archive_path: &Path,
+    node_dir: &Path,
+    manifest: &BackupManifest,
+) -> Result<()> {
+    let file = File::create(archive_path)
+        .map_err(|e| eyre!("Failed to create archive {}: {}", archive_path.display(), e))?;
+    let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest_bytes = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| eyre!("Failed to serialize manifest: {}", e))?;
+    let mut header = Header::new_gnu();
+    header.set_size(manifest_bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Local::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_FILE, manifest_bytes.as_slice())
+        .map_err(|e| eyre!("Failed to write manifest to archive: {}", e))?;
+
+    for file in &manifest.files {
+        builder
+            .append_path_with_name(
+                node_dir.join(&file.path),
+                Path::new(NODE_DIR).join(&file.path),
+            )
+            .map_err(|e| eyre!("Failed to add {} to archive: {}", file.path, e))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| eyre!("Failed to finish archive: {}", e))?;
+    Ok(())
+}
+
+// Unpack an archive into the staging directory and check it against its manifest.
+// The manifest comes first, as written by write_archive, and only the regular files it
+// lists are unpacked, anything else fails the restore.
+pub fn unpack_archive(archive_path: &Path, staging_dir: &Path) -> Result<BackupManifest> {
+    let file = File::open(archive_path)
+        .map_err(|e| eyre!("Failed to open archive {}: {}", archive_path.display(), e))?;
+    fs::create_dir_all(staging_dir)
+        .map_err(|e| eyre!("Failed to create staging directory: {}", e))?;
+    let mut archive = Archive::new(GzDecoder::new(file));
+    let mut entries = archive
+        .entries()
+        .map_err(|e| eyre!("Failed to read archive: {}", e))?;
+
+    let mut manifest_entry = entries
+        .next()
+        .ok_or_else(|| eyre!("Archive does not contain a manifest"))?
+        .map_err(|e| eyre!("Failed to read archive entry: {}", e))?;
+    if manifest_entry.path().ok().as_deref() != Some(Path::new(MANIFEST_FILE)) {
+        bail!("Archive does not start with a manifest");
+    }
+    let mut manifest_content = String::new();
+    manifest_entry
+        .read_to_string(&mut manifest_content)
+        .map_err(|e| eyre!("Failed to read manifest: {}", e))?;
+    let manifest: BackupManifest = serde_json::from_str(&manifest_content)
+        .map_err(|e| eyre!("Failed to parse manifest: {}", e))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        bail!(
+            "Backup format version {} is not supported",
+            manifest.format_version
+        );
+    }
+
+    let mut expected: HashSet<PathBuf> = manifest
+        .files
+        .iter()
+        .map(|file| Path::new(NODE_DIR).join(&file.path))
+        .collect();
+    for entry in entries {
+        let mut entry = entry.map_err(|e| eyre!("Failed to read archive entry: {}", e))?;
+        let path = entry
+            .path()
+            .map_err(|e| eyre!("Failed to read archive entry path: {}", e))?
+            .into_owned();
+        if !entry.header().entry_type().is_file() || !expected.remove(&path) {
+            bail!(
+                "Archive contains {}, which is not listed in its manifest",
+                path.display()
+            );
+        }
+        let unpacked = entry
+            .unpack_in(staging_dir)
+            .map_err(|e| eyre!("Failed to unpack {}: {}", path.display(), e))?;
+        if !unpacked {
+            bail!(
+                "Archive entry {} points outside the node directory",
+                path.display()
+            );
+        }
+    }
+
+    if let Some(path) = expected.iter().next() {
+        bail!("Archive is missing {}", path.display());
+    }
+
+    let node_dir = staging_dir.join(NODE_DIR);
+    for file in &manifest.files {
+        let checksum = sha256_file(&node_dir.join(&file.path))?;
+        if checksum != file.sha256 {
+            bail!("Checksum mismatch for {}", file.path);
+        }
+    }
+
+    Ok(manifest)
+}
+
+// Pick the first port from the preferred one upwards that is neither assigned to
+// another node nor currently in use
+pub fn allocate_port(preferred: u16, taken: &HashSet<u16>) -> Result<u16> {
+    (preferred..=u16::MAX)
+        .find(|port| !taken.contains(port) && !is_port_in_use(*port))
+        .ok_or_else(|| eyre!("No free port available from {}", preferred))
+}
+
+pub const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+pub fn backup_file_name(node_name: &str) -> String {
+    let timestamp = Local::now().format(BACKUP_TIMESTAMP_FORMAT);
+    format!("{}-{}.tar.gz", node_name, timestamp)
+}
+
+pub fn default_backup_path(manager: &NodeManager, node_name: &str) -> PathBuf {
+    get_backups_dir(manager).join(backup_file_name(node_name))
+}
+
+pub async fn backup_node(
+    manager: &NodeManager,
+    node_name: String,
+    destination: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let node_dir = get_nodes_dir(manager).join(&node_name);
+    if !node_dir.exists() {
+        bail!(ManagerError::NodeNotFound { node_name });
+    }
+
+    let archive_path = destination.unwrap_or_else(|| default_backup_path(manager, &node_name));
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| eyre!("Failed to create backup directory: {}", e))?;
+    }
+
+    // Stop the node so its database is consistent on disk, it is restarted afterwards
+    let was_running = is_node_process_running(manager, &node_name)?;
+    if was_running {
+        stop_node_process(manager, node_name.clone()).await?;
+    }
+
+    // Hashing and compressing the node directory blocks, so it runs off the async runtime
+    let result = tokio::task::spawn_blocking({
+        let manager = manager.clone();
+        let node_name = node_name.clone();
+        let archive_path = archive_path.clone();
+        move || -> Result<()> {
+            let manifest = BackupManifest {
+                format_version: BACKUP_FORMAT_VERSION,
+                node_name: node_name.clone(),
+                created_at: Local::now().to_rfc3339(),
+                manager_version: env!("CARGO_PKG_VERSION").to_string(),
+                meroctl_version: get_meroctl_version(&manager)
+                    .unwrap_or_else(|_| "unknown".to_string()),
+                node_ports: get_node_ports(&node_name, &manager)?,
+                settings: get_node_settings(&manager, &node_name)?,
+                files: build_manifest_files(&node_dir)?,
+            };
+            write_archive(&archive_path, &node_dir, &manifest)
+        }
+    })
+    .await
+    .map_err(|e| eyre!("Backup task failed: {}", e))
+    .and_then(|result| result);
+
+    if was_running {
+        start_node(manager, node_name.clone()).await?;
+    }
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+
+    Ok(archive_path)
+}
+
+pub fn restore_node(
+    manager: &NodeManager,
+    archive_path: PathBuf,
+    node_name: Option<String>,
+    server_port: Option<u32>,
+    swarm_port: Option<u32>,
+) -> Result<String> {
+    let nodes_dir = get_nodes_dir(manager);
+    let staging_dir = get_backups_dir(manager).join(format!(
+        ".restore-{}",
+        Local::now().format("%Y%m%dT%H%M%S%.f")
+    ));
+
+    let result = (|| -> Result<String> {
+        let manifest = unpack_archive(&archive_path, &staging_dir)?;
+        let node_name = node_name.unwrap_or_else(|| manifest.node_name.clone());
+        validate_node_name(&node_name)?;
+        let node_dir = nodes_dir.join(&node_name);
+        if node_dir.exists() {
+            bail!(ManagerError::NodeAlreadyExists { node_name });
+        }
+
+        // Explicit ports are checked like on import, the original ports are kept when
+        // they are free and new ones allocated otherwise
+        let mut taken = get_assigned_ports(manager)?;
+        if server_port.is_some() && server_port == swarm_port {
+            bail!("Server and swarm ports must be different");
+        }
+        for port in [server_port, swarm_port].into_iter().flatten() {
+            if !taken.insert(port as u16) {
+                bail!(ManagerError::PortInUse { port: port as u16 });
+            }
+        }
+        let server_port = match server_port {
+            Some(port) => port,
+            None => allocate_port(manifest.node_ports.server_port, &taken)? as u32,
+        };
+        taken.insert(server_port as u16);
+        let swarm_port = match swarm_port {
+            Some(port) => port,
+            None => allocate_port(manifest.node_ports.swarm_port, &taken)? as u32,
+        };
+
+        let staged_node_dir = staging_dir.join(NODE_DIR);
+        write_node_ports(&staged_node_dir, server_port, swarm_port)?;
+        fs::create_dir_all(&nodes_dir)
+            .map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
+        move_path(&staged_node_dir, &node_dir)
+            .map_err(|e| eyre!("Failed to move restored node into place: {}", e))?;
+
+        modify_settings(manager, |settings| {
+            settings
+                .workspace_mut()?
+                .nodes
+                .insert(node_name.clone(), manifest.settings.clone());
+            Ok(())
+        })?;
+
+        Ok(node_name)
+    })();
+
+    let _ = fs::remove_dir_all(&staging_dir);
+    let node_name = result?;
+
+    let log_file = create_log_file(manager, &node_name)
+        .map_err(|e| eyre!("Failed to create log file: {}", e))?;
+    {
+        let mut nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes.insert(
+            node_name.clone(),
+            NodeProcess {
+                process: None,
+                stdin: None,
+                output: Arc::new(Mutex::new(String::new())),
+                log_file: Some(log_file),
+            },
+        );
+    }
+
+    manager.events.publish_node(NodeEvent::Created {
+        node_name: node_name.clone(),
+    })?;
+
+    Ok(node_name)
+}
@@ -0,0 +1,186 @@
+use crate::{
+    backup::{backup_file_name, backup_node, BACKUP_TIMESTAMP_FORMAT},
+    logger::log_node_event,
+    manager::NodeManager,
+    store::{get_settings, modify_settings},
+    types::{AppSettings, BackupInterval, BackupSchedule},
+    utils::{get_backups_dir, is_node_process_running},
+};
+use chrono::{Local, NaiveDateTime};
+use eyre::{bail, eyre, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+// Recorded as the error of a schedule whose node was running, so the skip is only
+// logged once rather than on every check until the node is stopped
+const SKIPPED_RUNNING: &str = "Skipped because the node is running";
+
+pub fn validate_backup_schedule(schedule: &BackupSchedule) -> Result<()> {
+    if schedule.keep_last == 0 {
+        bail!("At least one snapshot must be kept");
+    }
+    Ok(())
+}
+
+fn interval_secs(interval: BackupInterval) -> i64 {
+    match interval {
+        BackupInterval::Hourly => 60 * 60,
+        BackupInterval::Daily => 24 * 60 * 60,
+        BackupInterval::Weekly => 7 * 24 * 60 * 60,
+    }
+}
+
+// A schedule that never ran, or whose last run is older than its interval, is due.
+// Runs missed while the application was closed are therefore caught up once on start.
+pub fn is_backup_due(schedule: &BackupSchedule, now: i64) -> bool {
+    match schedule.last_run_at {
+        Some(last_run_at) => now - last_run_at >= interval_secs(schedule.interval),
+        None => true,
+    }
+}
+
+// Snapshots are kept per workspace since node names are only unique within one
+pub fn snapshot_dir(manager: &NodeManager, settings: &AppSettings, node_name: &str) -> PathBuf {
+    settings
+        .backup_directory
+        .clone()
+        .unwrap_or_else(|| get_backups_dir(manager))
+        .join(&settings.active_workspace)
+        .join(node_name)
+}
+
+// Whether the file name is a snapshot of the node, the timestamp is checked so that
+// snapshots of a node named e.g. "node-1" are not taken for ones of "node"
+fn is_snapshot_of(file_name: &str, node_name: &str) -> bool {
+    file_name
+        .strip_prefix(node_name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|rest| rest.strip_suffix(".tar.gz"))
+        .map(|timestamp| NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).is_ok())
+        .unwrap_or(false)
+}
+
+// Delete the oldest snapshots so that only the last `keep_last` remain. Snapshot
+// names end with a sortable timestamp, so name order is creation order.
+pub fn prune_snapshots(dir: &Path, node_name: &str, keep_last: u32) -> Result<Vec<PathBuf>> {
+    let mut snapshots: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| eyre!("Failed to read snapshot directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| is_snapshot_of(name, node_name))
+                .unwrap_or(false)
+        })
+        .collect();
+    snapshots.sort();
+
+    let excess = snapshots.len().saturating_sub(keep_last as usize);
+    let removed: Vec<PathBuf> = snapshots.drain(..excess).collect();
+    for path in &removed {
+        fs::remove_file(path)
+            .map_err(|e| eyre!("Failed to remove snapshot {}: {}", path.display(), e))?;
+    }
+    Ok(removed)
+}
+
+async fn run_scheduled_backup(
+    manager: &NodeManager,
+    settings: &AppSettings,
+    node_name: &str,
+    schedule: &BackupSchedule,
+) -> Result<PathBuf> {
+    let dir = snapshot_dir(manager, settings, node_name);
+    let archive_path = backup_node(
+        manager,
+        node_name.to_string(),
+        Some(dir.join(backup_file_name(node_name))),
+    )
+    .await?;
+    prune_snapshots(&dir, node_name, schedule.keep_last)?;
+    Ok(archive_path)
+}
+
+pub async fn run_due_backups(manager: &NodeManager) -> Result<()> {
+    let settings = get_settings(manager)?;
+    let workspace_name = &settings.app.active_workspace;
+    let now = Local::now().timestamp();
+
+    // Only the active workspace is scheduled, the others catch up once activated
+    for (node_name, node) in &settings.workspace()?.nodes {
+        let Some(schedule) = &node.backup_schedule else {
+            continue;
+        };
+        if !is_backup_due(schedule, now) {
+            continue;
+        }
+
+        // Stopping a node is disruptive, so it is only done when the schedule opts in
+        if !schedule.stop_running_node && is_node_process_running(manager, node_name)? {
+            if schedule.last_error.as_deref() == Some(SKIPPED_RUNNING) {
+                continue;
+            }
+            log_node_event(
+                manager,
+                node_name,
+                &format!(
+                    "Scheduled backup of node '{}' skipped, the node is running",
+                    node_name
+                ),
+            )?;
+            modify_settings(manager, |settings| {
+                if let Some(schedule) = settings
+                    .workspaces
+                    .get_mut(workspace_name)
+                    .and_then(|w| w.nodes.get_mut(node_name))
+                    .and_then(|n| n.backup_schedule.as_mut())
+                {
+                    schedule.last_error = Some(SKIPPED_RUNNING.to_string());
+                }
+                Ok(())
+            })?;
+            continue;
+        }
+
+        let result = run_scheduled_backup(manager, &settings.app, node_name, schedule).await;
+        let message = match &result {
+            Ok(archive_path) => format!(
+                "Scheduled backup of node '{}' written to {}",
+                node_name,
+                archive_path.display()
+            ),
+            Err(e) => format!("Scheduled backup of node '{}' failed: {}", node_name, e),
+        };
+        log_node_event(manager, node_name, &message)?;
+
+        // The node may have been deleted or its schedule removed while the backup ran
+        let last_error = result.err().map(|e| e.to_string());
+        modify_settings(manager, |settings| {
+            if let Some(schedule) = settings
+                .workspaces
+                .get_mut(workspace_name)
+                .and_then(|w| w.nodes.get_mut(node_name))
+                .and_then(|n| n.backup_schedule.as_mut())
+            {
+                schedule.last_run_at = Some(now);
+                schedule.last_error = last_error;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+// Periodically run the due backups, to be spawned on the async runtime of the frontend
+pub async fn run_backup_scheduler(manager: NodeManager) {
+    loop {
+        if let Err(e) = run_due_backups(&manager).await {
+            log::error!(operation = "scheduled_backup"; "Error running scheduled backups: {:?}", e);
+        }
+        tokio::time::sleep(SCHEDULER_INTERVAL).await;
+    }
+}
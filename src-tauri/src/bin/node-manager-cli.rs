@@ -0,0 +1,520 @@
+// Headless frontend of the node manager, exposing the operations of the desktop app as
+// subcommands and as a long running daemon. It shares the nodes directory and settings
+// store with the desktop app.
+use clap::{Args, Parser, Subcommand};
+use eyre::{bail, eyre, Result};
+use node_multiplatform_tauri::backup::schedule::run_backup_scheduler;
+use node_multiplatform_tauri::backup::{backup_node, restore_node};
+use node_multiplatform_tauri::control::run_control_api;
+use node_multiplatform_tauri::diagnostics::create_diagnostic_bundle;
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::health::run_health_monitor;
+use node_multiplatform_tauri::limits::run_watchdog;
+use node_multiplatform_tauri::logger::app::init_manager_log;
+use node_multiplatform_tauri::logger::read_log_file;
+use node_multiplatform_tauri::manager::{
+    default_data_dir, default_log_dir, ManagerConfig, NodeManager,
+};
+use node_multiplatform_tauri::metrics::start_metrics_collector;
+use node_multiplatform_tauri::operations::groups::{restart_group, start_group, stop_group};
+use node_multiplatform_tauri::operations::import::import_node;
+use node_multiplatform_tauri::operations::{
+    create_node, delete_node, get_nodes, start_node, start_nodes_on_startup, stop_all_nodes,
+    stop_node_process, update_node_config,
+};
+use node_multiplatform_tauri::runtime::NodeRuntime;
+use node_multiplatform_tauri::store::{get_node_groups, get_run_node_on_startup, get_settings};
+use node_multiplatform_tauri::trash::{list_trash, restore_from_trash, start_trash_purger};
+use node_multiplatform_tauri::types::{ImportMode, LaunchProfile, NodeEvent, OperationResult};
+use node_multiplatform_tauri::utils::{binary_relative_path, get_node_ports};
+use node_multiplatform_tauri::workspace::{list_workspaces, switch_workspace};
+use serde::Serialize;
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+#[derive(Parser)]
+#[command(
+    name = "node-manager-cli",
+    version,
+    about = "Manage Calimero nodes without the desktop app"
+)]
+struct Cli {
+    /// Data directory shared with the desktop app, holding the settings store and nodes
+    #[arg(long, env = "NODE_MANAGER_DATA_DIR", global = true)]
+    data_dir: Option<PathBuf>,
+    /// meroctl binary, by default the one next to this executable
+    #[arg(long, env = "MEROCTL_PATH", global = true)]
+    meroctl: Option<PathBuf>,
+    /// Directory of the manager's application log, by default the desktop app's
+    #[arg(long, env = "NODE_MANAGER_LOG_DIR", global = true)]
+    log_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the nodes of the active workspace
+    List,
+    /// Initialize a new node
+    Create {
+        name: String,
+        #[arg(long)]
+        server_port: u32,
+        #[arg(long)]
+        swarm_port: u32,
+        #[arg(long)]
+        run_on_startup: bool,
+        #[command(flatten)]
+        launch_profile: LaunchProfileArgs,
+    },
+    /// Rename a node or change its ports, unset options keep their current value
+    Update {
+        name: String,
+        #[arg(long)]
+        rename: Option<String>,
+        #[arg(long)]
+        server_port: Option<u32>,
+        #[arg(long)]
+        swarm_port: Option<u32>,
+        #[arg(long)]
+        run_on_startup: Option<bool>,
+    },
+    /// Run a node in the foreground until it exits or the command is interrupted
+    Start { name: String },
+    /// Stop a running node
+    Stop { name: String },
+    /// Move a node to the trash
+    Delete { name: String },
+    /// Print the log of a node
+    Logs { name: String },
+    /// Manage node groups
+    #[command(subcommand)]
+    Groups(GroupsCommand),
+    /// Write a backup archive of a node
+    Backup {
+        name: String,
+        #[arg(long)]
+        destination: Option<PathBuf>,
+    },
+    /// Write a diagnostic bundle with the config, recent logs and state of nodes
+    Diagnostics {
+        #[arg(required = true)]
+        names: Vec<String>,
+        #[arg(long)]
+        destination: Option<PathBuf>,
+    },
+    /// Restore a node from a backup archive
+    Restore {
+        archive: PathBuf,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        server_port: Option<u32>,
+        #[arg(long)]
+        swarm_port: Option<u32>,
+    },
+    /// Manage deleted nodes
+    #[command(subcommand)]
+    Trash(TrashCommand),
+    /// Bring an existing meroctl node directory under management
+    Import {
+        source: PathBuf,
+        #[arg(long)]
+        name: Option<String>,
+        /// Link the directory instead of copying it
+        #[arg(long)]
+        link: bool,
+        #[arg(long)]
+        server_port: Option<u32>,
+        #[arg(long)]
+        swarm_port: Option<u32>,
+    },
+    /// Manage workspaces
+    #[command(subcommand)]
+    Workspaces(WorkspacesCommand),
+    /// Print the settings
+    Settings,
+    /// Run the nodes marked to run on startup together with the background services
+    /// until interrupted
+    Daemon,
+}
+
+#[derive(Subcommand)]
+enum GroupsCommand {
+    List,
+    /// Start a group in the foreground until the command is interrupted
+    Start {
+        name: String,
+    },
+    Stop {
+        name: String,
+    },
+    /// Restart a group in the foreground until the command is interrupted
+    Restart {
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommand {
+    List,
+    Restore {
+        id: String,
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspacesCommand {
+    List,
+    Switch { name: String },
+}
+
+#[derive(Args)]
+struct LaunchProfileArgs {
+    /// Environment variables of the node process, as KEY=VALUE
+    #[arg(long = "env", value_parser = parse_env_var)]
+    env: Vec<(String, String)>,
+    #[arg(long = "init-arg", allow_hyphen_values = true)]
+    init_args: Vec<String>,
+    #[arg(long = "run-arg", allow_hyphen_values = true)]
+    run_args: Vec<String>,
+}
+
+fn parse_env_var(value: &str) -> Result<(String, String)> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => bail!("Expected KEY=VALUE, got {}", value),
+    }
+}
+
+// meroctl is looked up next to this executable, either below the same relative path
+// the desktop app bundles it under or directly
+fn default_binary_path() -> Result<PathBuf> {
+    let exe_dir = env::current_exe()
+        .map_err(|e| eyre!("Failed to get executable path: {}", e))?
+        .parent()
+        .map(PathBuf::from)
+        .ok_or_else(|| eyre!("Failed to get executable directory"))?;
+    let bundled = exe_dir.join(binary_relative_path());
+    if bundled.exists() {
+        return Ok(bundled);
+    }
+    Ok(exe_dir.join("meroctl"))
+}
+
+// Print the result in the same shape the desktop commands return it
+fn report<T: Serialize>(result: Result<T>, message: &str) -> ExitCode {
+    let code = if result.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    };
+    match serde_json::to_string_pretty(&OperationResult::from_result(result, message)) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize result: {}", e),
+    }
+    code
+}
+
+async fn wait_for_shutdown() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate())
+            .map_err(|e| eyre!("Failed to listen for SIGTERM: {}", e))?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result.map_err(|e| eyre!("Failed to listen for Ctrl-C: {}", e))?
+            }
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c()
+        .await
+        .map_err(|e| eyre!("Failed to listen for Ctrl-C: {}", e))?;
+    Ok(())
+}
+
+// Print node output and lifecycle events to the terminal
+fn print_events(manager: &NodeManager) -> Result<()> {
+    manager.events.subscribe(|event| match event {
+        ManagerEvent::Output { node_name, line } => println!("[{}] {}", node_name, line),
+        ManagerEvent::Node(event) => {
+            if let Ok(json) = serde_json::to_string(event) {
+                eprintln!("node event: {}", json);
+            }
+        }
+        ManagerEvent::HealthChanged(event) => {
+            if let Ok(json) = serde_json::to_string(event) {
+                eprintln!("health changed: {}", json);
+            }
+        }
+        _ => {}
+    })
+}
+
+// Nodes whose process was started by this command and has not exited yet
+fn running_here(manager: &NodeManager, node_names: &[String]) -> Vec<String> {
+    let Ok(nodes) = manager.nodes.lock() else {
+        return Vec::new();
+    };
+    node_names
+        .iter()
+        .filter(|node_name| nodes.get(*node_name).is_some_and(|n| n.process.is_some()))
+        .cloned()
+        .collect()
+}
+
+// Keep the started nodes in the foreground until they all exit or the command is
+// interrupted, then stop the ones still running. Nodes run by the desktop app or a
+// daemon are left alone.
+async fn run_in_foreground(manager: &NodeManager, node_names: Vec<String>) -> Result<()> {
+    let exited = Arc::new(Notify::new());
+    manager.events.subscribe({
+        let exited = exited.clone();
+        move |event| {
+            if let ManagerEvent::Node(NodeEvent::Exited { .. }) = event {
+                exited.notify_one();
+            }
+        }
+    })?;
+
+    loop {
+        if running_here(manager, &node_names).is_empty() {
+            return Ok(());
+        }
+        tokio::select! {
+            result = wait_for_shutdown() => {
+                result?;
+                break;
+            }
+            _ = exited.notified() => {}
+        }
+    }
+
+    for node_name in running_here(manager, &node_names) {
+        stop_node_process(manager, node_name).await?;
+    }
+    Ok(())
+}
+
+async fn run_daemon(manager: &NodeManager) -> Result<()> {
+    start_nodes_on_startup(manager).await?;
+
+    tokio::spawn(run_health_monitor(manager.clone()));
+    start_metrics_collector(manager.clone());
+    tokio::spawn(run_watchdog(manager.clone()));
+    tokio::spawn(run_backup_scheduler(manager.clone()));
+    start_trash_purger(manager.clone());
+    tokio::spawn(run_control_api(manager.clone()));
+
+    wait_for_shutdown().await?;
+    stop_all_nodes(manager).await
+}
+
+async fn run(cli: Cli) -> Result<ExitCode> {
+    let data_dir = match cli.data_dir {
+        Some(data_dir) => data_dir,
+        None => default_data_dir()?,
+    };
+    let binary_path = match cli.meroctl {
+        Some(binary_path) => binary_path,
+        None => default_binary_path()?,
+    };
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir,
+        runtime: NodeRuntime::new(binary_path),
+    })?;
+    let log_dir = match cli.log_dir {
+        Some(log_dir) => log_dir,
+        None => default_log_dir()?,
+    };
+    init_manager_log(&manager, &log_dir)?;
+
+    let code = match cli.command {
+        Command::List => report(get_nodes(&manager), "Nodes fetched successfully"),
+        Command::Create {
+            name,
+            server_port,
+            swarm_port,
+            run_on_startup,
+            launch_profile,
+        } => {
+            let launch_profile = LaunchProfile {
+                env: launch_profile.env.into_iter().collect(),
+                init_args: launch_profile.init_args,
+                run_args: launch_profile.run_args,
+            };
+            let result = create_node(
+                &manager,
+                name,
+                server_port,
+                swarm_port,
+                run_on_startup,
+                launch_profile,
+            )
+            .await;
+            report(result, "Node initialized successfully")
+        }
+        Command::Update {
+            name,
+            rename,
+            server_port,
+            swarm_port,
+            run_on_startup,
+        } => {
+            let result = async {
+                let config = get_node_ports(&name, &manager)?;
+                let run_on_startup = match run_on_startup {
+                    Some(run_on_startup) => run_on_startup,
+                    None => get_run_node_on_startup(&manager, &name)?,
+                };
+                update_node_config(
+                    &manager,
+                    name.clone(),
+                    rename.unwrap_or_else(|| name.clone()),
+                    server_port.unwrap_or(config.server_port as u32),
+                    swarm_port.unwrap_or(config.swarm_port as u32),
+                    run_on_startup,
+                )
+                .await
+            }
+            .await;
+            report(result, "Node updated successfully")
+        }
+        Command::Start { name } => {
+            print_events(&manager)?;
+            let result = async {
+                start_node(&manager, name.clone()).await?;
+                run_in_foreground(&manager, vec![name]).await
+            }
+            .await;
+            report(result, "Node ran successfully")
+        }
+        Command::Stop { name } => report(
+            stop_node_process(&manager, name).await,
+            "Node stopped successfully",
+        ),
+        Command::Delete { name } => report(
+            delete_node(&manager, name).await,
+            "Node deleted successfully",
+        ),
+        Command::Logs { name } => match read_log_file(&manager, &name) {
+            Ok(log) => {
+                print!("{}", log);
+                ExitCode::SUCCESS
+            }
+            Err(e) => report::<()>(Err(e), ""),
+        },
+        Command::Groups(GroupsCommand::List) => report(
+            get_node_groups(&manager),
+            "Node groups fetched successfully",
+        ),
+        Command::Groups(GroupsCommand::Start { name }) => {
+            print_events(&manager)?;
+            let result = async {
+                start_group(&manager, name.clone()).await?;
+                run_in_foreground(&manager, group_members(&manager, &name)?).await
+            }
+            .await;
+            report(result, "Node group ran successfully")
+        }
+        Command::Groups(GroupsCommand::Stop { name }) => report(
+            stop_group(&manager, name).await,
+            "Node group stopped successfully",
+        ),
+        Command::Groups(GroupsCommand::Restart { name }) => {
+            print_events(&manager)?;
+            let result = async {
+                restart_group(&manager, name.clone()).await?;
+                run_in_foreground(&manager, group_members(&manager, &name)?).await
+            }
+            .await;
+            report(result, "Node group ran successfully")
+        }
+        Command::Backup { name, destination } => report(
+            backup_node(&manager, name, destination)
+                .await
+                .map(|archive_path| archive_path.display().to_string()),
+            "Node backed up successfully",
+        ),
+        Command::Diagnostics { names, destination } => report(
+            create_diagnostic_bundle(&manager, names, destination)
+                .map(|bundle_path| bundle_path.display().to_string()),
+            "Diagnostic bundle created successfully",
+        ),
+        Command::Restore {
+            archive,
+            name,
+            server_port,
+            swarm_port,
+        } => report(
+            restore_node(&manager, archive, name, server_port, swarm_port),
+            "Node restored successfully",
+        ),
+        Command::Trash(TrashCommand::List) => {
+            report(list_trash(&manager), "Trash fetched successfully")
+        }
+        Command::Trash(TrashCommand::Restore { id, name }) => report(
+            restore_from_trash(&manager, id, name),
+            "Node restored successfully",
+        ),
+        Command::Import {
+            source,
+            name,
+            link,
+            server_port,
+            swarm_port,
+        } => {
+            let mode = if link {
+                ImportMode::Link
+            } else {
+                ImportMode::Copy
+            };
+            report(
+                import_node(&manager, source, name, mode, server_port, swarm_port),
+                "Node imported successfully",
+            )
+        }
+        Command::Workspaces(WorkspacesCommand::List) => {
+            report(list_workspaces(&manager), "Workspaces fetched successfully")
+        }
+        Command::Workspaces(WorkspacesCommand::Switch { name }) => report(
+            switch_workspace(&manager, name),
+            "Workspace switched successfully",
+        ),
+        Command::Settings => report(get_settings(&manager), "Settings fetched successfully"),
+        Command::Daemon => {
+            print_events(&manager)?;
+            report(run_daemon(&manager).await, "Daemon stopped successfully")
+        }
+    };
+
+    Ok(code)
+}
+
+fn group_members(manager: &NodeManager, group_name: &str) -> Result<Vec<String>> {
+    get_node_groups(manager)?
+        .into_iter()
+        .find(|g| g.name == group_name)
+        .map(|g| g.members.into_iter().map(|m| m.node_name).collect())
+        .ok_or_else(|| eyre!("Group not found: {}", group_name))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
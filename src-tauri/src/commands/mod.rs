@@ -1,60 +1,84 @@
+use std::path::PathBuf;
 use tauri::State;
 
 use crate::{
-    logger::read_log_file,
+    admin::{AdminClient, AdminHealth, Application, Context, ContextIdentity, ContextMembership},
+    backup::{backup_node as backup, restore_node as restore},
+    diagnostics::create_diagnostic_bundle as create_bundle,
+    jsonrpc::{clear_rpc_history, get_rpc_history, replay_rpc_request, send_rpc_request},
+    logger::{app::read_app_log, read_log_file},
+    manager::{run_blocking, NodeManager},
+    metrics::get_node_metrics,
+    operations::groups::{delete_group, restart_group, save_group, start_group, stop_group},
+    operations::import::import_node as import,
     operations::{
         create_node, delete_node as delete, get_node_output, get_nodes, open_admin_dashboard,
-        send_input_to_node, start_node as start, stop_node_process, update_node_config,
+        send_input_to_node, start_node as start, stop_node_process, update_app_settings,
+        update_node_config,
+    },
+    store::{get_node_groups, get_settings},
+    subscriptions::{
+        get_context_events, list_subscriptions, subscribe_to_contexts, unsubscribe_from_contexts,
+    },
+    trash::{list_trash, restore_from_trash},
+    types::{
+        AppLogEntry, AppLogLevel, ContextEvent, ImportMode, LaunchProfile, NodeGroup, NodeInfo,
+        NodeMetricsSample, OperationResult, RpcHistoryEntry, RpcRequest, Settings,
+        SubscriptionStatus, TrashedNode, WorkspaceInfo,
+    },
+    workspace::{
+        delete_workspace as delete_ws, list_workspaces, save_workspace as save_ws,
+        switch_workspace as switch_ws,
     },
-    types::{AppState, NodeInfo, OperationResult},
 };
 
 #[tauri::command]
 pub async fn initialize_node(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
     server_port: u32,
     swarm_port: u32,
     run_on_startup: bool,
+    launch_profile: Option<LaunchProfile>,
 ) -> Result<OperationResult, String> {
-    match create_node(state, node_name, server_port, swarm_port, run_on_startup).await {
-        Ok(true) => Ok(OperationResult {
+    match create_node(
+        &state,
+        node_name,
+        server_port,
+        swarm_port,
+        run_on_startup,
+        launch_profile.unwrap_or_default(),
+    )
+    .await
+    {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Node initialized successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Node initialization failed".to_string(),
-            data: None,
-        }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
-pub fn fetch_nodes(state: State<'_, AppState>) -> Result<OperationResult<Vec<NodeInfo>>, String> {
-    match get_nodes(state) {
+pub fn fetch_nodes(
+    state: State<'_, NodeManager>,
+) -> Result<OperationResult<Vec<NodeInfo>>, String> {
+    match get_nodes(&state) {
         Ok(nodes) => Ok(OperationResult {
             success: true,
             message: "Nodes fetched successfully".to_string(),
             data: Some(nodes),
+            error: None,
         }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn update_node(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     original_node_name: String,
     node_name: String,
     server_port: u32,
@@ -62,7 +86,7 @@ pub async fn update_node(
     run_on_startup: bool,
 ) -> Result<OperationResult, String> {
     match update_node_config(
-        state,
+        &state,
         original_node_name,
         node_name,
         server_port,
@@ -71,88 +95,61 @@ pub async fn update_node(
     )
     .await
     {
-        Ok(true) => Ok(OperationResult {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Node updated successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Node update failed".to_string(),
-            data: None,
-        }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn start_node(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
 ) -> Result<OperationResult, String> {
-    match start(state, node_name).await {
-        Ok(true) => Ok(OperationResult {
+    match start(&state, node_name).await {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Node started successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Node start failed".to_string(),
-            data: None,
-        }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn get_node_current_output(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
 ) -> Result<OperationResult<String>, String> {
-    match get_node_output(state, node_name) {
+    match get_node_output(&state, node_name) {
         Ok(output) => Ok(OperationResult {
             success: true,
             message: "Node output fetched successfully".to_string(),
             data: Some(output),
+            error: None,
         }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn stop_node(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
 ) -> Result<OperationResult, String> {
-    match stop_node_process(state, node_name).await {
-        Ok(true) => Ok(OperationResult {
+    match stop_node_process(&state, node_name).await {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Node stopped successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Node stop failed".to_string(),
-            data: None,
-        }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
@@ -160,85 +157,789 @@ pub async fn stop_node(
 pub async fn send_input(
     node_name: String,
     input: String,
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
 ) -> Result<OperationResult, String> {
-    match send_input_to_node(node_name, input, state) {
-        Ok(true) => Ok(OperationResult {
+    match send_input_to_node(node_name, input, &state) {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Input sent successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Input sending failed".to_string(),
-            data: None,
-        }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn get_node_log(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
 ) -> Result<OperationResult<String>, String> {
-    match read_log_file(state, &node_name) {
+    match read_log_file(&state, &node_name) {
         Ok(log) => Ok(OperationResult {
             success: true,
             message: "Node log fetched successfully".to_string(),
             data: Some(log),
+            error: None,
         }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
-            data: None,
-        }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
 pub async fn delete_node(
-    state: State<'_, AppState>,
+    state: State<'_, NodeManager>,
     node_name: String,
 ) -> Result<OperationResult, String> {
-    match delete(state, node_name).await {
-        Ok(true) => Ok(OperationResult {
+    match delete(&state, node_name).await {
+        Ok(()) => Ok(OperationResult {
             success: true,
             message: "Node deleted successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Ok(false) => Ok(OperationResult {
-            success: false,
-            message: "Node deletion failed".to_string(),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn open_dashboard(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult, String> {
+    match open_admin_dashboard(&state, node_name) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Dashboard opened successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn fetch_node_groups(
+    state: State<'_, NodeManager>,
+) -> Result<OperationResult<Vec<NodeGroup>>, String> {
+    match get_node_groups(&state) {
+        Ok(groups) => Ok(OperationResult {
+            success: true,
+            message: "Node groups fetched successfully".to_string(),
+            data: Some(groups),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn save_node_group(
+    state: State<'_, NodeManager>,
+    group: NodeGroup,
+) -> Result<OperationResult, String> {
+    match save_group(&state, group) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Node group saved successfully".to_string(),
             data: None,
+            error: None,
         }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
 
 #[tauri::command]
-pub async fn open_dashboard(
-    state: State<'_, AppState>,
+pub fn delete_node_group(
+    state: State<'_, NodeManager>,
+    group_name: String,
+) -> Result<OperationResult, String> {
+    match delete_group(&state, group_name) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Node group deleted successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn start_node_group(
+    state: State<'_, NodeManager>,
+    group_name: String,
+) -> Result<OperationResult, String> {
+    match start_group(&state, group_name).await {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Node group started successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_node_group(
+    state: State<'_, NodeManager>,
+    group_name: String,
+) -> Result<OperationResult, String> {
+    match stop_group(&state, group_name).await {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Node group stopped successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_node_group(
+    state: State<'_, NodeManager>,
+    group_name: String,
+) -> Result<OperationResult, String> {
+    match restart_group(&state, group_name).await {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Node group restarted successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn fetch_node_metrics(
+    state: State<'_, NodeManager>,
     node_name: String,
+) -> Result<OperationResult<Vec<NodeMetricsSample>>, String> {
+    match get_node_metrics(&state, &node_name) {
+        Ok(samples) => Ok(OperationResult {
+            success: true,
+            message: "Node metrics fetched successfully".to_string(),
+            data: Some(samples),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn fetch_settings(state: State<'_, NodeManager>) -> Result<OperationResult<Settings>, String> {
+    match get_settings(&state) {
+        Ok(settings) => Ok(OperationResult {
+            success: true,
+            message: "Settings fetched successfully".to_string(),
+            data: Some(settings),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn save_settings(
+    state: State<'_, NodeManager>,
+    settings: Settings,
 ) -> Result<OperationResult, String> {
-    match open_admin_dashboard(state.app_handle.clone(), node_name) {
+    // Auto launch follows through the RunAppOnStartupChanged event, as for the control API
+    match update_app_settings(&state, settings) {
         Ok(_) => Ok(OperationResult {
             success: true,
-            message: "Dashboard opened successfully".to_string(),
+            message: "Settings updated successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn backup_node(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    destination: Option<PathBuf>,
+) -> Result<OperationResult<String>, String> {
+    match backup(&state, node_name, destination).await {
+        Ok(archive_path) => Ok(OperationResult {
+            success: true,
+            message: "Node backed up successfully".to_string(),
+            data: Some(archive_path.display().to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn create_diagnostic_bundle(
+    state: State<'_, NodeManager>,
+    node_names: Vec<String>,
+    destination: Option<PathBuf>,
+) -> Result<OperationResult<String>, String> {
+    match run_blocking(&state, move |manager| {
+        create_bundle(manager, node_names, destination)
+    })
+    .await
+    {
+        Ok(bundle_path) => Ok(OperationResult {
+            success: true,
+            message: "Diagnostic bundle created successfully".to_string(),
+            data: Some(bundle_path.display().to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn restore_node(
+    state: State<'_, NodeManager>,
+    archive_path: PathBuf,
+    node_name: Option<String>,
+    server_port: Option<u32>,
+    swarm_port: Option<u32>,
+) -> Result<OperationResult<String>, String> {
+    match run_blocking(&state, move |manager| {
+        restore(manager, archive_path, node_name, server_port, swarm_port)
+    })
+    .await
+    {
+        Ok(node_name) => Ok(OperationResult {
+            success: true,
+            message: "Node restored successfully".to_string(),
+            data: Some(node_name),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn fetch_trash(
+    state: State<'_, NodeManager>,
+) -> Result<OperationResult<Vec<TrashedNode>>, String> {
+    match list_trash(&state) {
+        Ok(trashed_nodes) => Ok(OperationResult {
+            success: true,
+            message: "Trash fetched successfully".to_string(),
+            data: Some(trashed_nodes),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn restore_trashed_node(
+    state: State<'_, NodeManager>,
+    id: String,
+    node_name: Option<String>,
+) -> Result<OperationResult<String>, String> {
+    match run_blocking(&state, move |manager| {
+        restore_from_trash(manager, id, node_name)
+    })
+    .await
+    {
+        Ok(node_name) => Ok(OperationResult {
+            success: true,
+            message: "Node restored successfully".to_string(),
+            data: Some(node_name),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn import_node(
+    state: State<'_, NodeManager>,
+    source: PathBuf,
+    node_name: Option<String>,
+    mode: ImportMode,
+    server_port: Option<u32>,
+    swarm_port: Option<u32>,
+) -> Result<OperationResult<String>, String> {
+    match run_blocking(&state, move |manager| {
+        import(manager, source, node_name, mode, server_port, swarm_port)
+    })
+    .await
+    {
+        Ok(node_name) => Ok(OperationResult {
+            success: true,
+            message: "Node imported successfully".to_string(),
+            data: Some(node_name),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn fetch_workspaces(
+    state: State<'_, NodeManager>,
+) -> Result<OperationResult<Vec<WorkspaceInfo>>, String> {
+    match list_workspaces(&state) {
+        Ok(workspaces) => Ok(OperationResult {
+            success: true,
+            message: "Workspaces fetched successfully".to_string(),
+            data: Some(workspaces),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn save_workspace(
+    state: State<'_, NodeManager>,
+    name: String,
+    home: Option<PathBuf>,
+) -> Result<OperationResult, String> {
+    match save_ws(&state, name, home) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Workspace saved successfully".to_string(),
             data: None,
+            error: None,
         }),
-        Err(e) => Ok(OperationResult {
-            success: false,
-            message: e.to_string(),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn delete_workspace(
+    state: State<'_, NodeManager>,
+    name: String,
+) -> Result<OperationResult, String> {
+    match delete_ws(&state, name) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Workspace deleted successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub fn switch_workspace(
+    state: State<'_, NodeManager>,
+    name: String,
+) -> Result<OperationResult, String> {
+    match switch_ws(&state, name) {
+        Ok(_) => Ok(OperationResult {
+            success: true,
+            message: "Workspace switched successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn check_node_admin_health(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult<AdminHealth>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.health().await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Admin API is reachable".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_node_applications(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult<Vec<Application>>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.list_applications().await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Applications fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn install_node_application(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    url: String,
+    hash: Option<String>,
+    metadata: Option<Vec<u8>>,
+) -> Result<OperationResult<String>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client
+            .install_application(url, hash, metadata.unwrap_or_default())
+            .await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Application installed successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn install_node_dev_application(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    path: PathBuf,
+    metadata: Option<Vec<u8>>,
+) -> Result<OperationResult<String>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client
+            .install_dev_application(path, metadata.unwrap_or_default())
+            .await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Application installed successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_node_contexts(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult<Vec<Context>>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.list_contexts().await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Contexts fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn create_node_context(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    application_id: String,
+    initialization_params: Option<Vec<u8>>,
+) -> Result<OperationResult<ContextMembership>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client
+            .create_context(application_id, initialization_params.unwrap_or_default())
+            .await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Context created successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_node_context(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    context_id: String,
+) -> Result<OperationResult, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.delete_context(&context_id).await
+    }
+    .await;
+    match result {
+        Ok(()) => Ok(OperationResult {
+            success: true,
+            message: "Context deleted successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn invite_to_node_context(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    context_id: String,
+    inviter_id: String,
+    invitee_id: String,
+) -> Result<OperationResult<String>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client
+            .invite_to_context(context_id, inviter_id, invitee_id)
+            .await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Invitation created successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn join_node_context(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    private_key: String,
+    invitation_payload: String,
+) -> Result<OperationResult<ContextMembership>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.join_context(private_key, invitation_payload).await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Context joined successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_node_context_identities(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    context_id: String,
+) -> Result<OperationResult<Vec<String>>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.list_context_identities(&context_id).await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Context identities fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn generate_node_context_identity(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult<ContextIdentity>, String> {
+    let result = async {
+        let client = AdminClient::for_node(&state, &node_name)?;
+        client.generate_context_identity().await
+    }
+    .await;
+    match result {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Context identity generated successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn send_node_rpc_request(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    request: RpcRequest,
+) -> Result<OperationResult<RpcHistoryEntry>, String> {
+    match send_rpc_request(&state, &node_name, request).await {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Request sent successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn replay_node_rpc_request(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    id: u64,
+) -> Result<OperationResult<RpcHistoryEntry>, String> {
+    match replay_rpc_request(&state, &node_name, id).await {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Request replayed successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_node_rpc_history(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult<Vec<RpcHistoryEntry>>, String> {
+    match get_rpc_history(&state, &node_name) {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Request history fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn clear_node_rpc_history(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult, String> {
+    match clear_rpc_history(&state, &node_name) {
+        Ok(()) => Ok(OperationResult {
+            success: true,
+            message: "Request history cleared successfully".to_string(),
             data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn subscribe_node_contexts(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    context_ids: Vec<String>,
+) -> Result<OperationResult<SubscriptionStatus>, String> {
+    match subscribe_to_contexts(&state, node_name, context_ids) {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Subscribed to contexts successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn unsubscribe_node_contexts(
+    state: State<'_, NodeManager>,
+    node_name: String,
+) -> Result<OperationResult, String> {
+    match unsubscribe_from_contexts(&state, &node_name) {
+        Ok(()) => Ok(OperationResult {
+            success: true,
+            message: "Unsubscribed from contexts successfully".to_string(),
+            data: None,
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_subscriptions(
+    state: State<'_, NodeManager>,
+) -> Result<OperationResult<Vec<SubscriptionStatus>>, String> {
+    match list_subscriptions(&state) {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Subscriptions fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_node_context_events(
+    state: State<'_, NodeManager>,
+    node_name: String,
+    context_id: Option<String>,
+) -> Result<OperationResult<Vec<ContextEvent>>, String> {
+    match get_context_events(&state, &node_name, context_id.as_deref()) {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Context events fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
+        }),
+        Err(e) => Ok(OperationResult::failure(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_app_log(
+    level: Option<AppLogLevel>,
+    limit: Option<usize>,
+) -> Result<OperationResult<Vec<AppLogEntry>>, String> {
+    match read_app_log(level, limit) {
+        Ok(data) => Ok(OperationResult {
+            success: true,
+            message: "Application log fetched successfully".to_string(),
+            data: Some(data),
+            error: None,
         }),
+        Err(e) => Ok(OperationResult::failure(e)),
     }
 }
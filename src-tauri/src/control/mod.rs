@@ -0,0 +1,723 @@
+use crate::{
+    admin::AdminClient,
+    backup::{backup_node, restore_node},
+    diagnostics::create_diagnostic_bundle,
+    jsonrpc::{clear_rpc_history, get_rpc_history, replay_rpc_request, send_rpc_request},
+    logger::{app::read_app_log, read_log_file},
+    manager::{run_blocking, NodeManager},
+    metrics::get_node_metrics,
+    operations::groups::{delete_group, restart_group, save_group, start_group, stop_group},
+    operations::import::import_node,
+    operations::{
+        create_node, delete_node, get_node_output, get_nodes, open_admin_dashboard,
+        send_input_to_node, start_node, stop_node_process, update_app_settings, update_node_config,
+    },
+    store::{get_node_groups, get_settings},
+    subscriptions::{
+        get_context_events, list_subscriptions, subscribe_to_contexts, unsubscribe_from_contexts,
+    },
+    trash::{list_trash, restore_from_trash},
+    types::{
+        AppLogLevel, ImportMode, LaunchProfile, NodeGroup, OperationResult, RpcRequest, Settings,
+    },
+    workspace::{delete_workspace, list_workspaces, save_workspace, switch_workspace},
+};
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use eyre::{eyre, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tokio::net::TcpListener;
+
+pub const TOKEN_FILE: &str = "control_api.token";
+
+// A request names one of the commands in commands/mod.rs and passes its arguments
+// the way the frontend passes them to invoke, e.g.
+// {"command": "start_node", "args": {"nodeName": "node1"}}
+#[derive(Deserialize, Debug)]
+#[serde(tag = "command", content = "args", rename_all = "snake_case")]
+pub enum ControlRequest {
+    #[serde(rename_all = "camelCase")]
+    InitializeNode {
+        node_name: String,
+        server_port: u32,
+        swarm_port: u32,
+        run_on_startup: bool,
+        #[serde(default)]
+        launch_profile: Option<LaunchProfile>,
+    },
+    FetchNodes,
+    #[serde(rename_all = "camelCase")]
+    UpdateNode {
+        original_node_name: String,
+        node_name: String,
+        server_port: u32,
+        swarm_port: u32,
+        run_on_startup: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    StartNode {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetNodeCurrentOutput {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StopNode {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SendInput {
+        node_name: String,
+        input: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetNodeLog {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteNode {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    OpenDashboard {
+        node_name: String,
+    },
+    FetchNodeGroups,
+    SaveNodeGroup {
+        group: NodeGroup,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteNodeGroup {
+        group_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StartNodeGroup {
+        group_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StopNodeGroup {
+        group_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RestartNodeGroup {
+        group_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    FetchNodeMetrics {
+        node_name: String,
+    },
+    FetchSettings,
+    SaveSettings {
+        settings: Settings,
+    },
+    #[serde(rename_all = "camelCase")]
+    BackupNode {
+        node_name: String,
+        #[serde(default)]
+        destination: Option<PathBuf>,
+    },
+    #[serde(rename_all = "camelCase")]
+    CreateDiagnosticBundle {
+        node_names: Vec<String>,
+        #[serde(default)]
+        destination: Option<PathBuf>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RestoreNode {
+        archive_path: PathBuf,
+        #[serde(default)]
+        node_name: Option<String>,
+        #[serde(default)]
+        server_port: Option<u32>,
+        #[serde(default)]
+        swarm_port: Option<u32>,
+    },
+    FetchTrash,
+    #[serde(rename_all = "camelCase")]
+    RestoreTrashedNode {
+        id: String,
+        #[serde(default)]
+        node_name: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ImportNode {
+        source: PathBuf,
+        #[serde(default)]
+        node_name: Option<String>,
+        mode: ImportMode,
+        #[serde(default)]
+        server_port: Option<u32>,
+        #[serde(default)]
+        swarm_port: Option<u32>,
+    },
+    FetchWorkspaces,
+    SaveWorkspace {
+        name: String,
+        #[serde(default)]
+        home: Option<PathBuf>,
+    },
+    DeleteWorkspace {
+        name: String,
+    },
+    SwitchWorkspace {
+        name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CheckNodeAdminHealth {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    FetchNodeApplications {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    InstallNodeApplication {
+        node_name: String,
+        url: String,
+        #[serde(default)]
+        hash: Option<String>,
+        #[serde(default)]
+        metadata: Option<Vec<u8>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    InstallNodeDevApplication {
+        node_name: String,
+        path: PathBuf,
+        #[serde(default)]
+        metadata: Option<Vec<u8>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    FetchNodeContexts {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CreateNodeContext {
+        node_name: String,
+        application_id: String,
+        #[serde(default)]
+        initialization_params: Option<Vec<u8>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteNodeContext {
+        node_name: String,
+        context_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    InviteToNodeContext {
+        node_name: String,
+        context_id: String,
+        inviter_id: String,
+        invitee_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    JoinNodeContext {
+        node_name: String,
+        private_key: String,
+        invitation_payload: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    FetchNodeContextIdentities {
+        node_name: String,
+        context_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    GenerateNodeContextIdentity {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SendNodeRpcRequest {
+        node_name: String,
+        request: RpcRequest,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReplayNodeRpcRequest {
+        node_name: String,
+        id: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    FetchNodeRpcHistory {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ClearNodeRpcHistory {
+        node_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SubscribeNodeContexts {
+        node_name: String,
+        context_ids: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    UnsubscribeNodeContexts {
+        node_name: String,
+    },
+    FetchSubscriptions,
+    #[serde(rename_all = "camelCase")]
+    FetchNodeContextEvents {
+        node_name: String,
+        #[serde(default)]
+        context_id: Option<String>,
+    },
+    FetchAppLog {
+        #[serde(default)]
+        level: Option<AppLogLevel>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+}
+
+fn respond<T: Serialize>(result: Result<T>, message: &str) -> Value {
+    serde_json::to_value(OperationResult::from_result(result, message))
+        .unwrap_or_else(|e| Value::String(format!("Failed to serialize result: {}", e)))
+}
+
+// Run a request and return the OperationResult the matching command returns
+pub async fn handle_request(manager: &NodeManager, request: ControlRequest) -> Value {
+    match request {
+        ControlRequest::InitializeNode {
+            node_name,
+            server_port,
+            swarm_port,
+            run_on_startup,
+            launch_profile,
+        } => respond(
+            create_node(
+                manager,
+                node_name,
+                server_port,
+                swarm_port,
+                run_on_startup,
+                launch_profile.unwrap_or_default(),
+            )
+            .await,
+            "Node initialized successfully",
+        ),
+        ControlRequest::FetchNodes => respond(get_nodes(manager), "Nodes fetched successfully"),
+        ControlRequest::UpdateNode {
+            original_node_name,
+            node_name,
+            server_port,
+            swarm_port,
+            run_on_startup,
+        } => respond(
+            update_node_config(
+                manager,
+                original_node_name,
+                node_name,
+                server_port,
+                swarm_port,
+                run_on_startup,
+            )
+            .await,
+            "Node updated successfully",
+        ),
+        ControlRequest::StartNode { node_name } => respond(
+            start_node(manager, node_name).await,
+            "Node started successfully",
+        ),
+        ControlRequest::GetNodeCurrentOutput { node_name } => respond(
+            get_node_output(manager, node_name),
+            "Node output fetched successfully",
+        ),
+        ControlRequest::StopNode { node_name } => respond(
+            stop_node_process(manager, node_name).await,
+            "Node stopped successfully",
+        ),
+        ControlRequest::SendInput { node_name, input } => respond(
+            send_input_to_node(node_name, input, manager),
+            "Input sent successfully",
+        ),
+        ControlRequest::GetNodeLog { node_name } => respond(
+            read_log_file(manager, &node_name),
+            "Node log fetched successfully",
+        ),
+        ControlRequest::DeleteNode { node_name } => respond(
+            delete_node(manager, node_name).await,
+            "Node deleted successfully",
+        ),
+        ControlRequest::OpenDashboard { node_name } => respond(
+            open_admin_dashboard(manager, node_name),
+            "Dashboard opened successfully",
+        ),
+        ControlRequest::FetchNodeGroups => {
+            respond(get_node_groups(manager), "Node groups fetched successfully")
+        }
+        ControlRequest::SaveNodeGroup { group } => {
+            respond(save_group(manager, group), "Node group saved successfully")
+        }
+        ControlRequest::DeleteNodeGroup { group_name } => respond(
+            delete_group(manager, group_name),
+            "Node group deleted successfully",
+        ),
+        ControlRequest::StartNodeGroup { group_name } => respond(
+            start_group(manager, group_name).await,
+            "Node group started successfully",
+        ),
+        ControlRequest::StopNodeGroup { group_name } => respond(
+            stop_group(manager, group_name).await,
+            "Node group stopped successfully",
+        ),
+        ControlRequest::RestartNodeGroup { group_name } => respond(
+            restart_group(manager, group_name).await,
+            "Node group restarted successfully",
+        ),
+        ControlRequest::FetchNodeMetrics { node_name } => respond(
+            get_node_metrics(manager, &node_name),
+            "Node metrics fetched successfully",
+        ),
+        ControlRequest::FetchSettings => {
+            respond(get_settings(manager), "Settings fetched successfully")
+        }
+        ControlRequest::SaveSettings { settings } => respond(
+            update_app_settings(manager, settings),
+            "Settings updated successfully",
+        ),
+        ControlRequest::BackupNode {
+            node_name,
+            destination,
+        } => respond(
+            backup_node(manager, node_name, destination)
+                .await
+                .map(|archive_path| archive_path.display().to_string()),
+            "Node backed up successfully",
+        ),
+        ControlRequest::CreateDiagnosticBundle {
+            node_names,
+            destination,
+        } => respond(
+            run_blocking(manager, move |manager| {
+                create_diagnostic_bundle(manager, node_names, destination)
+            })
+            .await
+            .map(|bundle_path| bundle_path.display().to_string()),
+            "Diagnostic bundle created successfully",
+        ),
+        ControlRequest::RestoreNode {
+            archive_path,
+            node_name,
+            server_port,
+            swarm_port,
+        } => respond(
+            run_blocking(manager, move |manager| {
+                restore_node(manager, archive_path, node_name, server_port, swarm_port)
+            })
+            .await,
+            "Node restored successfully",
+        ),
+        ControlRequest::FetchTrash => respond(list_trash(manager), "Trash fetched successfully"),
+        ControlRequest::RestoreTrashedNode { id, node_name } => respond(
+            run_blocking(manager, move |manager| {
+                restore_from_trash(manager, id, node_name)
+            })
+            .await,
+            "Node restored successfully",
+        ),
+        ControlRequest::ImportNode {
+            source,
+            node_name,
+            mode,
+            server_port,
+            swarm_port,
+        } => respond(
+            run_blocking(manager, move |manager| {
+                import_node(manager, source, node_name, mode, server_port, swarm_port)
+            })
+            .await,
+            "Node imported successfully",
+        ),
+        ControlRequest::FetchWorkspaces => {
+            respond(list_workspaces(manager), "Workspaces fetched successfully")
+        }
+        ControlRequest::SaveWorkspace { name, home } => respond(
+            save_workspace(manager, name, home),
+            "Workspace saved successfully",
+        ),
+        ControlRequest::DeleteWorkspace { name } => respond(
+            delete_workspace(manager, name),
+            "Workspace deleted successfully",
+        ),
+        ControlRequest::SwitchWorkspace { name } => respond(
+            switch_workspace(manager, name),
+            "Workspace switched successfully",
+        ),
+        ControlRequest::CheckNodeAdminHealth { node_name } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.health().await
+            }
+            .await,
+            "Admin API is reachable",
+        ),
+        ControlRequest::FetchNodeApplications { node_name } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.list_applications().await
+            }
+            .await,
+            "Applications fetched successfully",
+        ),
+        ControlRequest::InstallNodeApplication {
+            node_name,
+            url,
+            hash,
+            metadata,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client
+                    .install_application(url, hash, metadata.unwrap_or_default())
+                    .await
+            }
+            .await,
+            "Application installed successfully",
+        ),
+        ControlRequest::InstallNodeDevApplication {
+            node_name,
+            path,
+            metadata,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client
+                    .install_dev_application(path, metadata.unwrap_or_default())
+                    .await
+            }
+            .await,
+            "Application installed successfully",
+        ),
+        ControlRequest::FetchNodeContexts { node_name } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.list_contexts().await
+            }
+            .await,
+            "Contexts fetched successfully",
+        ),
+        ControlRequest::CreateNodeContext {
+            node_name,
+            application_id,
+            initialization_params,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client
+                    .create_context(application_id, initialization_params.unwrap_or_default())
+                    .await
+            }
+            .await,
+            "Context created successfully",
+        ),
+        ControlRequest::DeleteNodeContext {
+            node_name,
+            context_id,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.delete_context(&context_id).await
+            }
+            .await,
+            "Context deleted successfully",
+        ),
+        ControlRequest::InviteToNodeContext {
+            node_name,
+            context_id,
+            inviter_id,
+            invitee_id,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client
+                    .invite_to_context(context_id, inviter_id, invitee_id)
+                    .await
+            }
+            .await,
+            "Invitation created successfully",
+        ),
+        ControlRequest::JoinNodeContext {
+            node_name,
+            private_key,
+            invitation_payload,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.join_context(private_key, invitation_payload).await
+            }
+            .await,
+            "Context joined successfully",
+        ),
+        ControlRequest::FetchNodeContextIdentities {
+            node_name,
+            context_id,
+        } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.list_context_identities(&context_id).await
+            }
+            .await,
+            "Context identities fetched successfully",
+        ),
+        ControlRequest::GenerateNodeContextIdentity { node_name } => respond(
+            async {
+                let client = AdminClient::for_node(manager, &node_name)?;
+                client.generate_context_identity().await
+            }
+            .await,
+            "Context identity generated successfully",
+        ),
+        ControlRequest::SendNodeRpcRequest { node_name, request } => respond(
+            send_rpc_request(manager, &node_name, request).await,
+            "Request sent successfully",
+        ),
+        ControlRequest::ReplayNodeRpcRequest { node_name, id } => respond(
+            replay_rpc_request(manager, &node_name, id).await,
+            "Request replayed successfully",
+        ),
+        ControlRequest::FetchNodeRpcHistory { node_name } => respond(
+            get_rpc_history(manager, &node_name),
+            "Request history fetched successfully",
+        ),
+        ControlRequest::ClearNodeRpcHistory { node_name } => respond(
+            clear_rpc_history(manager, &node_name),
+            "Request history cleared successfully",
+        ),
+        ControlRequest::SubscribeNodeContexts {
+            node_name,
+            context_ids,
+        } => respond(
+            subscribe_to_contexts(manager, node_name, context_ids),
+            "Subscribed to contexts successfully",
+        ),
+        ControlRequest::UnsubscribeNodeContexts { node_name } => respond(
+            unsubscribe_from_contexts(manager, &node_name),
+            "Unsubscribed from contexts successfully",
+        ),
+        ControlRequest::FetchSubscriptions => respond(
+            list_subscriptions(manager),
+            "Subscriptions fetched successfully",
+        ),
+        ControlRequest::FetchNodeContextEvents {
+            node_name,
+            context_id,
+        } => respond(
+            get_context_events(manager, &node_name, context_id.as_deref()),
+            "Context events fetched successfully",
+        ),
+        ControlRequest::FetchAppLog { level, limit } => respond(
+            read_app_log(level, limit),
+            "Application log fetched successfully",
+        ),
+    }
+}
+
+#[derive(Clone)]
+struct ControlState {
+    manager: NodeManager,
+    token: Arc<String>,
+}
+
+pub fn router(manager: NodeManager, token: String) -> Router {
+    Router::new()
+        .route("/invoke", post(invoke))
+        .with_state(ControlState {
+            manager,
+            token: Arc::new(token),
+        })
+}
+
+// The token is checked before the body is parsed, so unauthenticated callers learn
+// nothing about the request format
+async fn invoke(State(state): State<ControlState>, headers: HeaderMap, body: Bytes) -> Response {
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        // Compared in constant time so the token can't be guessed byte by byte from timings
+        .is_some_and(|token| bool::from(token.as_bytes().ct_eq(state.token.as_bytes())));
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+    }
+
+    let request = serde_json::from_slice::<Value>(&body).and_then(|mut body| {
+        // Commands without arguments accept an empty args object, as invoke does
+        if body["args"].as_object().is_some_and(|args| args.is_empty()) {
+            body["args"] = Value::Null;
+        }
+        serde_json::from_value::<ControlRequest>(body)
+    });
+    let request = match request {
+        Ok(request) => request,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, format!("Invalid request: {}", e)).into_response()
+        }
+    };
+    Json(handle_request(&state.manager, request).await).into_response()
+}
+
+pub async fn serve(manager: NodeManager, listener: TcpListener, token: String) -> Result<()> {
+    axum::serve(listener, router(manager, token))
+        .await
+        .map_err(|e| eyre!("Control API server failed: {}", e))
+}
+
+// Read the token scripts authenticate with, creating a random one readable only by
+// the current user on first use
+pub fn load_or_create_token(data_dir: &Path) -> Result<String> {
+    let token_path = data_dir.join(TOKEN_FILE);
+    if let Ok(token) = fs::read_to_string(&token_path) {
+        let token = token.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(&token_path)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .map_err(|e| eyre!("Failed to write control API token: {}", e))?;
+
+    Ok(token)
+}
+
+// Serve the control API on the loopback interface if it is enabled in the settings,
+// to be spawned on the async runtime of the frontend
+pub async fn run_control_api(manager: NodeManager) {
+    let result = async {
+        let settings = get_settings(&manager)?.app.control_api;
+        if !settings.enabled {
+            return Ok(());
+        }
+        let token = load_or_create_token(&manager.config.data_dir)?;
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port));
+        let listener = TcpListener::bind(address)
+            .await
+            .map_err(|e| eyre!("Failed to bind control API to {}: {}", address, e))?;
+        serve(manager.clone(), listener, token).await
+    }
+    .await;
+    if let Err(e) = result {
+        log::error!(operation = "control_api"; "Error running control API: {:?}", e);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    error::ManagerError,
+    health::current_health,
+    logger::{
+        app::{format_entry, read_app_log},
+        read_log_file,
+    },
+    manager::NodeManager,
+    redaction::{export_redactor, Redactor},
+    store::get_settings,
+    types::{DiagnosticSummary, NodeDiagnostics, PortKind, PortState},
+    utils::{
+        get_diagnostics_dir, get_meroctl_version, get_node_ports, get_nodes_dir,
+        is_node_process_running, is_port_in_use,
+    },
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
+use flate2::{write::GzEncoder, Compression};
+use shared_utils::determine_bin_data;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tar::{Builder, Header};
+
+const SUMMARY_FILE: &str = "summary.json";
+const SETTINGS_FILE: &str = "settings.json";
+const APP_LOG_FILE: &str = "manager.log";
+// Only the end of the logs is included, which is where the trouble usually is
+const LOG_TAIL_LINES: usize = 1000;
+
+// Path inside the bundle and content of a file
+type BundleFile = (String, Vec<u8>);
+
+fn tail_lines(content: &str, count: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+pub fn diagnostic_bundle_file_name() -> String {
+    let timestamp = Local::now().format("%Y%m%dT%H%M%S");
+    format!("diagnostics-{}.tar.gz", timestamp)
+}
+
+// The state of a node and its files for the bundle. Anything that cannot be read is
+// recorded in the node's errors instead of failing the whole bundle.
+fn collect_node(
+    manager: &NodeManager,
+    redactor: &Redactor,
+    node_name: &str,
+) -> Result<(NodeDiagnostics, Vec<BundleFile>)> {
+    let node_dir = get_nodes_dir(manager).join(node_name);
+    if !node_dir.exists() {
+        bail!(ManagerError::NodeNotFound {
+            node_name: node_name.to_string()
+        });
+    }
+
+    let mut errors = Vec::new();
+    let mut files = Vec::new();
+
+    // An error means the node is running outside of the application
+    let is_running = match is_node_process_running(manager, node_name) {
+        Ok(is_running) => is_running,
+        Err(e) => {
+            errors.push(format!("Failed to check node process: {}", e));
+            false
+        }
+    };
+    let health = current_health(manager, node_name, is_running)?;
+
+    let ports = match get_node_ports(node_name, manager) {
+        Ok(config) => vec![
+            PortState {
+                kind: PortKind::Server,
+                port: config.server_port,
+                in_use: is_port_in_use(config.server_port),
+            },
+            PortState {
+                kind: PortKind::Swarm,
+                port: config.swarm_port,
+                in_use: is_port_in_use(config.swarm_port),
+            },
+        ],
+        Err(e) => {
+            errors.push(format!("Failed to read node ports: {}", e));
+            Vec::new()
+        }
+    };
+
+    match fs::read_to_string(node_dir.join("config.toml")) {
+        Ok(config) => files.push((
+            format!("nodes/{}/config.toml", node_name),
+            redactor.redact(&config).into_bytes(),
+        )),
+        Err(e) => errors.push(format!("Failed to read config file: {}", e)),
+    }
+    match read_log_file(manager, node_name) {
+        Ok(log) => files.push((
+            format!("nodes/{}/node.log", node_name),
+            redactor
+                .redact(&tail_lines(&log, LOG_TAIL_LINES))
+                .into_bytes(),
+        )),
+        Err(e) => errors.push(format!("Failed to read node log: {}", e)),
+    }
+
+    let diagnostics = NodeDiagnostics {
+        node_name: node_name.to_string(),
+        is_running,
+        health,
+        ports,
+        files: files.iter().map(|(path, _)| path.clone()).collect(),
+        errors,
+    };
+    Ok((diagnostics, files))
+}
+
+fn append_file(builder: &mut Builder<GzEncoder<File>>, path: &str, data: &[u8]) -> Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Local::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, data)
+        .map_err(|e| eyre!("Failed to add {} to bundle: {}", path, e))
+}
+
+fn write_bundle(bundle_path: &Path, files: &[BundleFile]) -> Result<()> {
+    let file = File::create(bundle_path)
+        .map_err(|e| eyre!("Failed to create bundle {}: {}", bundle_path.display(), e))?;
+    let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+    for (path, data) in files {
+        append_file(&mut builder, path, data)?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| eyre!("Failed to finish bundle: {}", e))?;
+    Ok(())
+}
+
+// Gather the configuration, recent logs and state of the nodes, the manager's
+// settings and environment into a gzipped tarball for bug reports
+pub fn create_diagnostic_bundle(
+    manager: &NodeManager,
+    node_names: Vec<String>,
+    destination: Option<PathBuf>,
+) -> Result<PathBuf> {
+    if node_names.is_empty() {
+        bail!("Select at least one node for the diagnostic bundle");
+    }
+
+    // Secrets are masked in everything but the summary, which holds none
+    let redactor = export_redactor(manager)?;
+    let mut nodes = Vec::new();
+    let mut files = Vec::new();
+    for node_name in &node_names {
+        let (diagnostics, node_files) = collect_node(manager, &redactor, node_name)?;
+        nodes.push(diagnostics);
+        files.extend(node_files);
+    }
+
+    let settings = serde_json::to_string_pretty(&get_settings(manager)?)
+        .map_err(|e| eyre!("Failed to serialize settings: {}", e))?;
+    files.push((
+        SETTINGS_FILE.to_string(),
+        redactor.redact(&settings).into_bytes(),
+    ));
+
+    // The application log is only available once it has been initialized
+    if let Ok(entries) = read_app_log(None, Some(LOG_TAIL_LINES)) {
+        let log = entries
+            .iter()
+            .map(format_entry)
+            .collect::<Vec<_>>()
+            .join("\n");
+        files.push((APP_LOG_FILE.to_string(), redactor.redact(&log).into_bytes()));
+    }
+
+    let (os, arch, _) = determine_bin_data();
+    let summary = DiagnosticSummary {
+        created_at: Local::now().to_rfc3339(),
+        manager_version: env!("CARGO_PKG_VERSION").to_string(),
+        meroctl_version: get_meroctl_version(manager).unwrap_or_else(|_| "unknown".to_string()),
+        os,
+        arch,
+        nodes,
+    };
+    let summary = serde_json::to_vec_pretty(&summary)
+        .map_err(|e| eyre!("Failed to serialize summary: {}", e))?;
+    files.insert(0, (SUMMARY_FILE.to_string(), summary));
+
+    let bundle_path = destination
+        .unwrap_or_else(|| get_diagnostics_dir(manager).join(diagnostic_bundle_file_name()));
+    if let Some(parent) = bundle_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| eyre!("Failed to create diagnostics directory: {}", e))?;
+    }
+    if let Err(e) = write_bundle(&bundle_path, &files) {
+        let _ = fs::remove_file(&bundle_path);
+        return Err(e);
+    }
+
+    Ok(bundle_path)
+}
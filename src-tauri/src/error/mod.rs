@@ -0,0 +1,64 @@
+use crate::types::OperationResult;
+use serde::Serialize;
+use thiserror::Error;
+
+// Errors the frontend can act on. They are serialized with a stable `code` next to
+// their fields, e.g. {"code": "PORT_IN_USE", "port": 2428}.
+#[derive(Error, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ManagerError {
+    #[error("Port {port} is already in use")]
+    PortInUse { port: u16 },
+    #[error("Node not found: {node_name}")]
+    NodeNotFound { node_name: String },
+    #[error("Node with name {node_name} already exists")]
+    NodeAlreadyExists { node_name: String },
+    #[error("Node {node_name} is running")]
+    NodeRunning { node_name: String },
+    #[error("Node {node_name} is not running")]
+    NodeNotRunning { node_name: String },
+    #[error("Node with name {node_name} is already running outside of the application")]
+    NodeRunningExternally { node_name: String },
+    #[error("Failed to parse config {path}: {reason}")]
+    ConfigParse { path: String, reason: String },
+    #[error("meroctl binary not found at {path}")]
+    BinaryMissing { path: String },
+    #[error("{message}")]
+    Internal { message: String },
+}
+
+impl ManagerError {
+    // Find the typed error anywhere in the report's chain, falling back to Internal
+    pub fn from_report(report: &eyre::Report) -> Self {
+        report
+            .chain()
+            .find_map(|e| e.downcast_ref::<ManagerError>())
+            .cloned()
+            .unwrap_or_else(|| ManagerError::Internal {
+                message: report.to_string(),
+            })
+    }
+}
+
+impl<T> OperationResult<T> {
+    pub fn from_result(result: eyre::Result<T>, message: &str) -> Self {
+        match result {
+            Ok(data) => OperationResult {
+                success: true,
+                message: message.to_string(),
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => OperationResult::failure(e),
+        }
+    }
+
+    pub fn failure(report: eyre::Report) -> Self {
+        OperationResult {
+            success: false,
+            message: report.to_string(),
+            data: None,
+            error: Some(ManagerError::from_report(&report)),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::types::{ContextEvent, HealthChangedEvent, NodeEvent, NodeMetricsSample};
+use eyre::{eyre, Result};
+use std::sync::{Arc, Mutex};
+
+// Everything the node manager reports to its frontends, i.e. the desktop app windows
+// and tray or the headless daemon
+#[derive(Debug, Clone)]
+pub enum ManagerEvent {
+    Node(NodeEvent),
+    Output {
+        node_name: String,
+        line: String,
+    },
+    HealthChanged(HealthChangedEvent),
+    Metrics {
+        node_name: String,
+        sample: NodeMetricsSample,
+    },
+    ContextEvent {
+        node_name: String,
+        event: ContextEvent,
+    },
+    WorkspaceChanged {
+        name: String,
+    },
+    GroupsChanged,
+    SettingsChanged,
+    // Published along with `SettingsChanged`, the desktop app registers or removes
+    // itself from the login items
+    RunAppOnStartupChanged {
+        enabled: bool,
+    },
+}
+
+type Subscriber = Arc<dyn Fn(&ManagerEvent) + Send + Sync>;
+
+// Publishes manager events to the subscribers registered at startup
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBus {
+    pub fn subscribe(
+        &self,
+        subscriber: impl Fn(&ManagerEvent) + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.subscribers
+            .lock()
+            .map_err(|e| eyre!("Failed to lock event subscribers: {}", e))?
+            .push(Arc::new(subscriber));
+        Ok(())
+    }
+
+    // Subscribers are called outside of the lock so they can publish events themselves
+    pub fn publish(&self, event: ManagerEvent) -> Result<()> {
+        let subscribers = self
+            .subscribers
+            .lock()
+            .map_err(|e| eyre!("Failed to lock event subscribers: {}", e))?
+            .clone();
+        for subscriber in subscribers {
+            subscriber(&event);
+        }
+        Ok(())
+    }
+
+    pub fn publish_node(&self, event: NodeEvent) -> Result<()> {
+        self.publish(ManagerEvent::Node(event))
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    events::ManagerEvent,
+    manager::NodeManager,
+    types::{HealthChangedEvent, HealthStatus, NodeEvent, NodeHealth},
+    utils::{get_node_ports, is_node_process_running_in, is_port_in_use, process_snapshot},
+};
+use eyre::{eyre, Result};
+use std::time::{Duration, Instant};
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+const STARTUP_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+pub fn admin_api_health_url(server_port: u16) -> String {
+    format!("http://127.0.0.1:{}/admin-api/health", server_port)
+}
+
+// Probe an HTTP health endpoint, any 2xx response counts as reachable
+pub async fn probe_admin_api(url: &str) -> bool {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return false;
+    };
+    match client.get(url).send().await {
+        Ok(response) => response.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+// Classify a running node from the reachability of its admin API and swarm listener
+pub fn classify_health(
+    api_reachable: bool,
+    swarm_reachable: bool,
+    running_for: Duration,
+) -> HealthStatus {
+    match (api_reachable, swarm_reachable) {
+        (true, true) => HealthStatus::Healthy,
+        _ if running_for < STARTUP_GRACE_PERIOD => HealthStatus::Starting,
+        (true, false) | (false, true) => HealthStatus::Degraded,
+        (false, false) => HealthStatus::Unreachable,
+    }
+}
+
+pub async fn probe_node(server_port: u16, swarm_port: u16, running_for: Duration) -> HealthStatus {
+    let api_reachable = probe_admin_api(&admin_api_health_url(server_port)).await;
+    let swarm_reachable = is_port_in_use(swarm_port);
+    classify_health(api_reachable, swarm_reachable, running_for)
+}
+
+// Health as reported in NodeInfo, running nodes that were not probed yet count as starting
+pub fn current_health(
+    manager: &NodeManager,
+    node_name: &str,
+    is_running: bool,
+) -> Result<HealthStatus> {
+    if !is_running {
+        return Ok(HealthStatus::Stopped);
+    }
+    let health = manager
+        .health
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node health: {}", e))?;
+    Ok(match health.get(node_name).map(|h| h.status) {
+        Some(HealthStatus::Stopped) | None => HealthStatus::Starting,
+        Some(status) => status,
+    })
+}
+
+pub fn mark_node_started(manager: &NodeManager, node_name: &str) -> Result<()> {
+    set_node_health(
+        manager,
+        node_name,
+        NodeHealth {
+            status: HealthStatus::Starting,
+            started_at: Some(Instant::now()),
+        },
+    )?;
+    Ok(())
+}
+
+pub fn mark_node_stopped(manager: &NodeManager, node_name: &str) -> Result<()> {
+    set_node_health(
+        manager,
+        node_name,
+        NodeHealth {
+            status: HealthStatus::Stopped,
+            started_at: None,
+        },
+    )?;
+    Ok(())
+}
+
+// Store the new health and notify listeners if the status changed
+fn set_node_health(manager: &NodeManager, node_name: &str, health: NodeHealth) -> Result<()> {
+    let status = health.status;
+    let previous = {
+        let mut map = manager
+            .health
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node health: {}", e))?;
+        map.insert(node_name.to_string(), health)
+            .map(|h| h.status)
+            .unwrap_or(HealthStatus::Stopped)
+    };
+
+    if previous == status {
+        return Ok(());
+    }
+
+    manager
+        .events
+        .publish(ManagerEvent::HealthChanged(HealthChangedEvent {
+            node_name: node_name.to_string(),
+            previous,
+            status,
+        }))?;
+
+    // A node is ready the first time it turns healthy after being started
+    if previous == HealthStatus::Starting && status == HealthStatus::Healthy {
+        manager.events.publish_node(NodeEvent::Ready {
+            node_name: node_name.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+pub async fn refresh_health(manager: &NodeManager) -> Result<()> {
+    let node_names: Vec<String> = {
+        let nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes.keys().cloned().collect()
+    };
+
+    let system = process_snapshot();
+    for node_name in node_names {
+        // Nodes running outside of the application are probed as well
+        let is_running = !matches!(
+            is_node_process_running_in(&system, manager, &node_name),
+            Ok(false)
+        );
+        if !is_running {
+            set_node_health(
+                manager,
+                &node_name,
+                NodeHealth {
+                    status: HealthStatus::Stopped,
+                    started_at: None,
+                },
+            )?;
+            continue;
+        }
+
+        let Ok(config) = get_node_ports(&node_name, manager) else {
+            continue;
+        };
+        let started_at = {
+            let health = manager
+                .health
+                .lock()
+                .map_err(|e| eyre!("Failed to lock node health: {}", e))?;
+            health
+                .get(&node_name)
+                .and_then(|h| h.started_at)
+                .unwrap_or_else(Instant::now)
+        };
+        let status = probe_node(config.server_port, config.swarm_port, started_at.elapsed()).await;
+        set_node_health(
+            manager,
+            &node_name,
+            NodeHealth {
+                status,
+                started_at: Some(started_at),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+// Periodically probe the nodes, to be spawned on the async runtime of the frontend
+pub async fn run_health_monitor(manager: NodeManager) {
+    loop {
+        if let Err(e) = refresh_health(&manager).await {
+            log::error!(operation = "probe_health"; "Error probing node health: {:?}", e);
+        }
+        tokio::time::sleep(PROBE_INTERVAL).await;
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    error::ManagerError,
+    manager::NodeManager,
+    types::{RpcHistoryEntry, RpcRequest, RpcRequestKind, RpcResponse},
+    utils::{get_node_ports, is_port_in_use},
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Keep the last 50 requests per node
+const MAX_HISTORY: usize = 50;
+
+// Ids of the requests, which also identify them in the history
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<JsonRpcResult>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResult {
+    #[serde(default)]
+    output: Option<Value>,
+}
+
+// Client for the JSON-RPC endpoint a node serves on its server port
+#[derive(Clone)]
+pub struct JsonRpcClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl JsonRpcClient {
+    // url is the endpoint, e.g. http://127.0.0.1:2428/jsonrpc/dev
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| eyre!("Failed to create HTTP client: {}", e))?;
+        Ok(JsonRpcClient {
+            http,
+            url: url.into(),
+        })
+    }
+
+    // Client for a managed node, which must be serving on its server port
+    pub fn for_node(manager: &NodeManager, node_name: &str) -> Result<Self> {
+        let config = get_node_ports(node_name, manager)?;
+        if !is_port_in_use(config.server_port) {
+            bail!(ManagerError::NodeNotRunning {
+                node_name: node_name.to_string()
+            });
+        }
+        JsonRpcClient::new(format!(
+            "http://127.0.0.1:{}/jsonrpc/dev",
+            config.server_port
+        ))
+    }
+
+    pub async fn call(&self, id: u64, request: &RpcRequest) -> Result<RpcResponse> {
+        let method = match request.kind {
+            RpcRequestKind::Query => "query",
+            RpcRequestKind::Mutate => "mutate",
+        };
+        // Methods without arguments still take an empty object
+        let args = match &request.args {
+            Value::Null => json!({}),
+            args => args.clone(),
+        };
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": {
+                "contextId": request.context_id,
+                "method": request.method,
+                "argsJson": args,
+                "executorPublicKey": request.executor_public_key,
+            },
+        });
+
+        let started = Instant::now();
+        let response = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| eyre!("Failed to reach JSON-RPC endpoint: {}", e))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| eyre!("Failed to read JSON-RPC response: {}", e))?;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        if !status.is_success() {
+            bail!("JSON-RPC request failed with {}: {}", status, text.trim());
+        }
+
+        let response: JsonRpcResponse = serde_json::from_str(&text)
+            .map_err(|e| eyre!("Failed to parse JSON-RPC response: {}", e))?;
+        Ok(RpcResponse {
+            output: response.result.and_then(|result| result.output),
+            error: response.error,
+            duration_ms,
+        })
+    }
+}
+
+// Send a request to a running node and record it in the node's history
+pub async fn send_rpc_request(
+    manager: &NodeManager,
+    node_name: &str,
+    request: RpcRequest,
+) -> Result<RpcHistoryEntry> {
+    let client = JsonRpcClient::for_node(manager, node_name)?;
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let result = client.call(id, &request).await;
+
+    let (response, failure) = match &result {
+        Ok(response) => (Some(response.clone()), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let entry = RpcHistoryEntry {
+        id,
+        timestamp: Local::now().timestamp_millis(),
+        request,
+        response,
+        failure,
+    };
+    push_history_entry(manager, node_name, entry.clone())?;
+
+    result.map(|_| entry)
+}
+
+// Send a request from the node's history again, recording it as a new entry
+pub async fn replay_rpc_request(
+    manager: &NodeManager,
+    node_name: &str,
+    id: u64,
+) -> Result<RpcHistoryEntry> {
+    let request = get_rpc_history(manager, node_name)?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .map(|entry| entry.request)
+        .ok_or_else(|| eyre!("Request {} not found in the history of {}", id, node_name))?;
+    send_rpc_request(manager, node_name, request).await
+}
+
+// Requests sent to the node, oldest first
+pub fn get_rpc_history(manager: &NodeManager, node_name: &str) -> Result<Vec<RpcHistoryEntry>> {
+    let history = manager
+        .rpc_history
+        .lock()
+        .map_err(|e| eyre!("Failed to lock request history: {}", e))?;
+    Ok(history
+        .get(node_name)
+        .map(|entries| entries.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+pub fn clear_rpc_history(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let mut history = manager
+        .rpc_history
+        .lock()
+        .map_err(|e| eyre!("Failed to lock request history: {}", e))?;
+    history.remove(node_name);
+    Ok(())
+}
+
+fn push_history_entry(
+    manager: &NodeManager,
+    node_name: &str,
+    entry: RpcHistoryEntry,
+) -> Result<()> {
+    let mut history = manager
+        .rpc_history
+        .lock()
+        .map_err(|e| eyre!("Failed to lock request history: {}", e))?;
+    let entries = history
+        .entry(node_name.to_string())
+        .or_insert_with(VecDeque::new);
+    if entries.len() == MAX_HISTORY {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+    Ok(())
+}
@@ -1,7 +1,26 @@
+pub mod admin;
+pub mod backup;
+#[cfg(feature = "gui")]
 pub mod commands;
+pub mod control;
+pub mod diagnostics;
+pub mod error;
+pub mod events;
+pub mod health;
+pub mod jsonrpc;
+pub mod limits;
 pub mod logger;
+pub mod manager;
+pub mod metrics;
+pub mod notifications;
 pub mod operations;
+pub mod redaction;
+pub mod runtime;
 pub mod store;
+pub mod subscriptions;
+pub mod trash;
+#[cfg(feature = "gui")]
 pub mod tray;
 pub mod types;
 pub mod utils;
+pub mod workspace;
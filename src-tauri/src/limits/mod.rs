@@ -0,0 +1,332 @@
+use crate::{
+    logger::log_node_event,
+    manager::NodeManager,
+    operations::{start_node, stop_node_process},
+    store::get_node_resource_limits,
+    types::{ResourceLimits, WatchdogAction},
+    utils::{get_node_ports, is_node_process_running, is_port_in_use},
+};
+use eyre::{bail, eyre, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(10);
+// Number of consecutive checks a soft limit has to be exceeded before the watchdog acts
+const WATCHDOG_TOLERANCE: u32 = 3;
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(10);
+const PORT_RELEASE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub fn validate_resource_limits(limits: &ResourceLimits) -> Result<()> {
+    if limits.max_memory_mb == Some(0) || limits.soft_memory_mb == Some(0) {
+        bail!("Memory limits must be greater than zero");
+    }
+    if let (Some(soft), Some(max)) = (limits.soft_memory_mb, limits.max_memory_mb) {
+        if soft > max {
+            bail!(
+                "Soft memory limit ({} MB) cannot exceed the maximum memory ({} MB)",
+                soft,
+                max
+            );
+        }
+    }
+    if let Some(nice) = limits.nice {
+        if !(0..=19).contains(&nice) {
+            bail!("CPU niceness must be between 0 and 19");
+        }
+    }
+    if limits.max_open_files == Some(0) {
+        bail!("Maximum open files must be greater than zero");
+    }
+    if let Some(cpu) = limits.soft_cpu_percent {
+        if cpu <= 0.0 {
+            bail!("Soft CPU limit must be greater than zero");
+        }
+    }
+    Ok(())
+}
+
+// Apply the hard limits to the node process before it is spawned. Without a cgroup for
+// the node, the memory cap falls back to a data segment limit (RLIMIT_DATA), which
+// covers the heap and writable private mappings but not the address space a wasm
+// runtime reserves up front. macOS does not enforce it.
+#[cfg(unix)]
+pub fn apply_resource_limits(
+    command: &mut Command,
+    limits: &ResourceLimits,
+    use_rlimit_memory: bool,
+) {
+    use std::os::unix::process::CommandExt;
+
+    let max_memory = limits
+        .max_memory_mb
+        .filter(|_| use_rlimit_memory)
+        .map(|mb| mb * 1024 * 1024);
+    let max_open_files = limits.max_open_files;
+    let nice = limits.nice;
+
+    if max_memory.is_none() && max_open_files.is_none() && nice.is_none() {
+        return;
+    }
+
+    // SAFETY: only async-signal-safe libc calls are made between fork and exec
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(bytes) = max_memory {
+                let limit = libc::rlimit {
+                    rlim_cur: bytes as libc::rlim_t,
+                    rlim_max: bytes as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(files) = max_open_files {
+                let mut limit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // The soft limit can be lowered freely but never raised above the hard limit
+                limit.rlim_cur = (files as libc::rlim_t).min(limit.rlim_max);
+                if libc::setrlimit(libc::RLIMIT_NOFILE, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub fn apply_resource_limits(
+    _command: &mut Command,
+    _limits: &ResourceLimits,
+    _use_rlimit_memory: bool,
+) {
+}
+
+// Create a cgroup v2 child group with the memory limit if the application's own cgroup
+// allows it (e.g. a delegated systemd user slice). Returns None when no cgroup is usable.
+#[cfg(target_os = "linux")]
+pub fn prepare_cgroup(node_name: &str, limits: &ResourceLimits) -> Option<PathBuf> {
+    use std::fs;
+
+    let max_memory_mb = limits.max_memory_mb?;
+    let cgroup_root = Path::new("/sys/fs/cgroup");
+    if !cgroup_root.join("cgroup.controllers").exists() {
+        return None;
+    }
+
+    // On cgroup v2 the process belongs to a single hierarchy listed as "0::/path"
+    let own_cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let own_path = own_cgroups
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?;
+    let cgroup = cgroup_root
+        .join(own_path.trim_start_matches('/'))
+        .join(format!("calimero-node-{}", node_name));
+
+    fs::create_dir_all(&cgroup).ok()?;
+    let max_bytes = (max_memory_mb * 1024 * 1024).to_string();
+    if fs::write(cgroup.join("memory.max"), max_bytes).is_err() {
+        let _ = fs::remove_dir(&cgroup);
+        return None;
+    }
+    Some(cgroup)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn prepare_cgroup(_node_name: &str, _limits: &ResourceLimits) -> Option<PathBuf> {
+    None
+}
+
+pub fn attach_to_cgroup(cgroup: &Path, pid: u32) -> Result<()> {
+    std::fs::write(cgroup.join("cgroup.procs"), pid.to_string())
+        .map_err(|e| eyre!("Failed to move process {} into cgroup: {}", pid, e))
+}
+
+// Remove the node's cgroup once its process has exited, it cannot be removed while
+// any process is still attached
+pub fn remove_cgroup(cgroup: &Path) {
+    let _ = std::fs::remove_dir(cgroup);
+}
+
+// Apply the memory cap as a data segment limit to a process that is already running,
+// for when it could not be attached to its cgroup. Cgroups only exist on Linux.
+#[cfg(target_os = "linux")]
+pub fn limit_process_memory(pid: u32, limits: &ResourceLimits) -> Result<()> {
+    let Some(max_memory_mb) = limits.max_memory_mb else {
+        return Ok(());
+    };
+    let bytes = (max_memory_mb * 1024 * 1024) as libc::rlim_t;
+    let limit = libc::rlimit {
+        rlim_cur: bytes,
+        rlim_max: bytes,
+    };
+    // SAFETY: prlimit only reads the new limit and does not write the old one
+    if unsafe {
+        libc::prlimit(
+            pid as libc::pid_t,
+            libc::RLIMIT_DATA,
+            &limit,
+            std::ptr::null_mut(),
+        )
+    } != 0
+    {
+        bail!(
+            "Failed to limit memory of process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn limit_process_memory(_pid: u32, _limits: &ResourceLimits) -> Result<()> {
+    Ok(())
+}
+
+// Describe the exceeded soft limit, if any, based on the latest metrics sample
+fn soft_limit_violation(
+    manager: &NodeManager,
+    node_name: &str,
+    limits: &ResourceLimits,
+) -> Result<Option<String>> {
+    let metrics = manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?;
+    let Some(sample) = metrics.get(node_name).and_then(|samples| samples.back()) else {
+        return Ok(None);
+    };
+
+    if let Some(soft_memory_mb) = limits.soft_memory_mb {
+        let memory_mb = sample.memory_bytes / (1024 * 1024);
+        if memory_mb > soft_memory_mb {
+            return Ok(Some(format!(
+                "memory usage {} MB exceeds soft limit of {} MB",
+                memory_mb, soft_memory_mb
+            )));
+        }
+    }
+    if let Some(soft_cpu_percent) = limits.soft_cpu_percent {
+        if sample.cpu_percent > soft_cpu_percent {
+            return Ok(Some(format!(
+                "CPU usage {:.1}% exceeds soft limit of {:.1}%",
+                sample.cpu_percent, soft_cpu_percent
+            )));
+        }
+    }
+    Ok(None)
+}
+
+// Stop or restart the running nodes that exceeded a soft limit on WATCHDOG_TOLERANCE
+// consecutive checks, violations counts the checks per node so far
+pub async fn check_soft_limits(
+    manager: &NodeManager,
+    violations: &mut HashMap<String, u32>,
+) -> Result<()> {
+    let running_nodes: Vec<String> = {
+        let nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes
+            .iter()
+            .filter(|(_, node_process)| node_process.process.is_some())
+            .map(|(node_name, _)| node_name.clone())
+            .collect()
+    };
+    violations.retain(|node_name, _| running_nodes.contains(node_name));
+
+    // A failure for one node doesn't keep the others from being checked
+    for node_name in running_nodes {
+        if let Err(e) = check_node_soft_limits(manager, &node_name, violations).await {
+            log::error!(
+                node = node_name.as_str(), operation = "watchdog";
+                "Error checking resource limits of node {}: {:?}", node_name, e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn check_node_soft_limits(
+    manager: &NodeManager,
+    node_name: &str,
+    violations: &mut HashMap<String, u32>,
+) -> Result<()> {
+    let limits = get_node_resource_limits(manager, node_name)?;
+    let Some(reason) = soft_limit_violation(manager, node_name, &limits)? else {
+        violations.remove(node_name);
+        return Ok(());
+    };
+
+    let count = violations.entry(node_name.to_string()).or_insert(0);
+    *count += 1;
+    if *count < WATCHDOG_TOLERANCE {
+        return Ok(());
+    }
+    violations.remove(node_name);
+
+    match limits.watchdog_action {
+        WatchdogAction::Stop => {
+            log_node_event(
+                manager,
+                node_name,
+                &format!("Watchdog stopping node '{}': {}", node_name, reason),
+            )?;
+            stop_node_process(manager, node_name.to_string()).await?;
+        }
+        WatchdogAction::Restart => {
+            log_node_event(
+                manager,
+                node_name,
+                &format!("Watchdog restarting node '{}': {}", node_name, reason),
+            )?;
+            stop_node_process(manager, node_name.to_string()).await?;
+            wait_for_ports_released(manager, node_name).await?;
+            start_node(manager, node_name.to_string()).await?;
+        }
+    }
+    Ok(())
+}
+
+// The stopped process may hold on to its ports for a moment, and starting the node
+// again before they are released fails with PORT_IN_USE
+async fn wait_for_ports_released(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let ports = get_node_ports(node_name, manager)?;
+    let deadline = Instant::now() + PORT_RELEASE_TIMEOUT;
+    while is_node_process_running(manager, node_name)?
+        || is_port_in_use(ports.server_port)
+        || is_port_in_use(ports.swarm_port)
+    {
+        if Instant::now() >= deadline {
+            bail!("Node {} did not release its ports in time", node_name);
+        }
+        tokio::time::sleep(PORT_RELEASE_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+// Periodically check the soft limits, to be spawned on the async runtime of the frontend
+pub async fn run_watchdog(manager: NodeManager) {
+    let mut violations = HashMap::new();
+    loop {
+        if let Err(e) = check_soft_limits(&manager, &mut violations).await {
+            log::error!(operation = "watchdog"; "Error checking node resource limits: {:?}", e);
+        }
+        tokio::time::sleep(WATCHDOG_INTERVAL).await;
+    }
+}
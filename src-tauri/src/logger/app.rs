@@ -0,0 +1,212 @@
+// Application log of the manager itself, separate from the node logs. Entries are
+// written through the `log` macros as JSON lines, with the node and operation they
+// concern passed as key-values, e.g.
+// log::error!(node = node_name.as_str(), operation = "stop_node"; "Failed to stop node: {}", e)
+use crate::manager::NodeManager;
+use crate::store::get_settings;
+use crate::types::{AppLogEntry, AppLogLevel};
+use chrono::Local;
+use eyre::{eyre, Result};
+use log::kv::{Error as KvError, Key, Value, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+pub const APP_LOG_FILE: &str = "manager.log";
+const MAX_APP_LOG_SIZE: u64 = 5 * 1024 * 1024; // 5MB
+
+// Rotated logs are kept as manager.log.1 (newest) to manager.log.3
+const ROTATED_APP_LOGS: usize = 3;
+
+static APP_LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+struct AppLogger {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl AppLogger {
+    fn write_line(&self, line: &str) -> std::io::Result<()> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| std::io::Error::other("Application log lock is poisoned"))?;
+        if file.is_none() {
+            *file = Some(open_log_file(&self.path)?);
+        }
+        if let Some(log_file) = file.as_mut() {
+            writeln!(log_file, "{}", line)?;
+            if log_file.metadata()?.len() > MAX_APP_LOG_SIZE {
+                *file = None;
+                rotate_logs(&self.path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut context = ContextVisitor(BTreeMap::new());
+        let _ = record.key_values().visit(&mut context);
+        let entry = AppLogEntry {
+            timestamp: Local::now().to_rfc3339(),
+            level: level_of(record.level()),
+            message: record.args().to_string(),
+            context: context.0,
+        };
+
+        // Still printed for development and the headless CLI
+        eprintln!("{}", format_entry(&entry));
+        if let Ok(line) = serde_json::to_string(&entry) {
+            if let Err(e) = self.write_line(&line) {
+                eprintln!("Failed to write application log: {}", e);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+struct ContextVisitor(BTreeMap<String, String>);
+
+impl<'kvs> VisitSource<'kvs> for ContextVisitor {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+fn level_of(level: log::Level) -> AppLogLevel {
+    match level {
+        log::Level::Error => AppLogLevel::Error,
+        log::Level::Warn => AppLogLevel::Warn,
+        log::Level::Info => AppLogLevel::Info,
+        log::Level::Debug | log::Level::Trace => AppLogLevel::Debug,
+    }
+}
+
+fn level_filter(level: AppLogLevel) -> LevelFilter {
+    match level {
+        AppLogLevel::Error => LevelFilter::Error,
+        AppLogLevel::Warn => LevelFilter::Warn,
+        AppLogLevel::Info => LevelFilter::Info,
+        AppLogLevel::Debug => LevelFilter::Debug,
+    }
+}
+
+// e.g. 2024-10-01T12:00:00+02:00 ERROR [node=node1 operation=stop_node] Failed to stop node
+pub fn format_entry(entry: &AppLogEntry) -> String {
+    let level = format!("{:?}", entry.level).to_uppercase();
+    if entry.context.is_empty() {
+        return format!("{} {} {}", entry.timestamp, level, entry.message);
+    }
+    let context = entry
+        .context
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{} {} [{}] {}",
+        entry.timestamp, level, context, entry.message
+    )
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_log_path(path: &Path, index: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), index))
+}
+
+// Shift manager.log.N to manager.log.N+1, dropping the oldest
+fn rotate_logs(path: &Path) -> std::io::Result<()> {
+    for index in (1..ROTATED_APP_LOGS).rev() {
+        let from = rotated_log_path(path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_log_path(path, index + 1))?;
+        }
+    }
+    fs::rename(path, rotated_log_path(path, 1))
+}
+
+// Route the `log` macros to the application log in log_dir. The log can only be
+// initialized once per process.
+pub fn init_app_log(log_dir: &Path, level: AppLogLevel) -> Result<()> {
+    fs::create_dir_all(log_dir).map_err(|e| eyre!("Failed to create log directory: {}", e))?;
+    APP_LOG_DIR
+        .set(log_dir.to_path_buf())
+        .map_err(|_| eyre!("Application log is already initialized"))?;
+    log::set_boxed_logger(Box::new(AppLogger {
+        path: log_dir.join(APP_LOG_FILE),
+        file: Mutex::new(None),
+    }))
+    .map_err(|e| eyre!("Failed to initialize application log: {}", e))?;
+    set_app_log_level(level);
+    Ok(())
+}
+
+// Initialize the application log at the level in the manager's settings
+pub fn init_manager_log(manager: &NodeManager, log_dir: &Path) -> Result<()> {
+    init_app_log(log_dir, get_settings(manager)?.app.log_level)
+}
+
+pub fn set_app_log_level(level: AppLogLevel) {
+    log::set_max_level(level_filter(level));
+}
+
+pub fn app_log_dir() -> Option<&'static Path> {
+    APP_LOG_DIR.get().map(PathBuf::as_path)
+}
+
+// The last `limit` entries at or above the given level, oldest first, including the
+// rotated logs
+pub fn read_app_log(level: Option<AppLogLevel>, limit: Option<usize>) -> Result<Vec<AppLogEntry>> {
+    let log_dir = app_log_dir().ok_or_else(|| eyre!("Application log is not initialized"))?;
+    let path = log_dir.join(APP_LOG_FILE);
+
+    let mut paths: Vec<PathBuf> = (1..=ROTATED_APP_LOGS)
+        .rev()
+        .map(|index| rotated_log_path(&path, index))
+        .collect();
+    paths.push(path);
+
+    let mut entries = VecDeque::new();
+    for path in paths.iter().filter(|path| path.exists()) {
+        let file = File::open(path).map_err(|e| eyre!("Failed to open application log: {}", e))?;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| eyre!("Failed to read application log: {}", e))?;
+            // Skip lines that are not entries, e.g. a partial last line
+            let Ok(entry) = serde_json::from_str::<AppLogEntry>(&line) else {
+                continue;
+            };
+            if level.is_some_and(|level| entry.level > level) {
+                continue;
+            }
+            entries.push_back(entry);
+            if limit.is_some_and(|limit| entries.len() > limit) {
+                entries.pop_front();
+            }
+        }
+    }
+    Ok(entries.into())
+}
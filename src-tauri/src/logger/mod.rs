@@ -1,16 +1,19 @@
-use crate::types::AppState;
+use crate::manager::NodeManager;
+use crate::redaction::redact;
 use crate::utils::get_nodes_dir;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, copy, BufRead, BufReader, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use chrono::Local;
 use eyre::{eyre, Error, Result};
-use tauri::{AppHandle, State};
+
+pub mod app;
 
 const MAX_LOG_SIZE: usize = 5 * 1024 * 1024; // 5MB
 
-pub fn create_log_file(app_handle: &AppHandle, node_name: &str) -> Result<File, Error> {
-    let log_path = get_log_file_path(app_handle, node_name);
+pub fn create_log_file(manager: &NodeManager, node_name: &str) -> Result<File, Error> {
+    let log_path = get_log_file_path(manager, node_name);
 
     // Ensure the directory exists
     if let Some(parent) = log_path.parent() {
@@ -28,7 +31,9 @@ pub fn create_log_file(app_handle: &AppHandle, node_name: &str) -> Result<File,
     Ok(file)
 }
 
-pub fn write_to_log(file: &mut File, line: &str) -> Result<bool, Error> {
+// Append a line to a node log, with secrets redacted
+pub fn write_to_log(manager: &NodeManager, file: &mut File, line: &str) -> Result<bool, Error> {
+    let line = redact(manager, line)?;
     writeln!(file, "{}", line).map_err(|e| eyre!("Failed to write to log file: {}", e))?;
 
     check_log_size_and_trim(file)?;
@@ -63,12 +68,39 @@ fn check_log_size_and_trim(file: &mut File) -> io::Result<()> {
     Ok(())
 }
 
-pub fn get_log_file_path(app_handle: &AppHandle, node_name: &str) -> PathBuf {
-    get_nodes_dir(app_handle).join(node_name).join("node.log")
+// Record an event raised by the application itself in the node log
+pub fn log_node_event(manager: &NodeManager, node_name: &str, message: &str) -> Result<()> {
+    log::info!(node = node_name, operation = "node_event"; "{}", message);
+    let mut nodes = manager
+        .nodes
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+    if let Some(log_file) = nodes.get_mut(node_name).and_then(|n| n.log_file.as_mut()) {
+        let timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S.%6fZ");
+        write_to_log(manager, log_file, &format!("{} {}", timestamp, message))
+            .map_err(|e| eyre!("Failed to log event for node {}: {}", node_name, e))?;
+    }
+    Ok(())
+}
+
+// Nodes imported as links live in a directory owned by someone else, so their log is
+// kept next to the link in the workspace home rather than inside it
+pub fn get_log_file_path(manager: &NodeManager, node_name: &str) -> PathBuf {
+    node_log_path(&get_nodes_dir(manager), node_name)
+}
+
+// Log path of a node in any workspace home
+pub fn node_log_path(nodes_dir: &Path, node_name: &str) -> PathBuf {
+    let node_dir = nodes_dir.join(node_name);
+    if node_dir.is_symlink() {
+        nodes_dir.join(format!(".{}.log", node_name))
+    } else {
+        node_dir.join("node.log")
+    }
 }
 
-pub fn get_node_log_file(app_handle: &AppHandle, node_name: &str) -> Result<File, Error> {
-    let log_path = get_log_file_path(app_handle, node_name);
+pub fn get_node_log_file(manager: &NodeManager, node_name: &str) -> Result<File, Error> {
+    let log_path = get_log_file_path(manager, node_name);
     OpenOptions::new()
         .read(true)
         .append(true)
@@ -76,8 +108,8 @@ pub fn get_node_log_file(app_handle: &AppHandle, node_name: &str) -> Result<File
         .map_err(|e| eyre!("Failed to open log file: {}", e))
 }
 
-pub fn read_log_file(state: State<'_, AppState>, node_name: &str) -> Result<String, Error> {
-    let log_path = get_log_file_path(&state.app_handle, node_name);
+pub fn read_log_file(manager: &NodeManager, node_name: &str) -> Result<String, Error> {
+    let log_path = get_log_file_path(manager, node_name);
     let file = OpenOptions::new()
         .read(true)
         .open(log_path)
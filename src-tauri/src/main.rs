@@ -1,12 +1,19 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use node_multiplatform_tauri::backup::schedule::run_backup_scheduler;
 use node_multiplatform_tauri::commands;
+use node_multiplatform_tauri::control::run_control_api;
+use node_multiplatform_tauri::health::run_health_monitor;
+use node_multiplatform_tauri::limits::run_watchdog;
+use node_multiplatform_tauri::metrics::start_metrics_collector;
+use node_multiplatform_tauri::store::get_settings;
+use node_multiplatform_tauri::trash::start_trash_purger;
 use node_multiplatform_tauri::tray::menu::{create_menu, handle_menu_click};
-use node_multiplatform_tauri::tray::{handle_tray_click, update_tray_menu};
-use node_multiplatform_tauri::types::AppState;
+use node_multiplatform_tauri::tray::{handle_tray_click, subscribe_to_manager_events, update_tray_menu};
 use node_multiplatform_tauri::utils::setup::{
-    run_nodes_on_startup, setup_app_folders, setup_app_state, setup_auto_launch, setup_store
+    forward_events_to_windows, run_nodes_on_startup, setup_auto_launch, setup_node_manager,
+    show_desktop_notifications, sync_run_app_on_startup,
 };
 use tauri::{Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, WindowEvent};
 
@@ -19,17 +26,11 @@ fn main() -> eyre::Result<()> {
         .menu(menu)
         .setup(|app| {
             let app_handle = app.handle();
-            // Initialize store
-            let store = setup_store(app)?;
+            // Initialize the node manager, which loads the nodes of the active workspace
+            let manager = setup_node_manager(app)?;
 
-            // Initialize app folders
-            setup_app_folders(&app_handle)?;
-
-            // Get the run_app_on_startup value from the store
-            let run_app_on_startup = store
-                .get("run_app_on_startup")
-                .and_then(|value| value.as_bool())
-                .unwrap_or(true);
+            // Get the run_app_on_startup value from the settings
+            let run_app_on_startup = get_settings(&manager)?.app.run_app_on_startup;
 
             // Update the menu item state if necessary
             if run_app_on_startup {
@@ -42,22 +43,41 @@ fn main() -> eyre::Result<()> {
                 setup_auto_launch(&app_handle)?
             }
 
-            // Initialize app state
-            let app_state = setup_app_state(app_handle, store)?;
-            app.manage(app_state);
+            app.manage(manager.clone());
 
-            // Get a reference to the managed state
-            let state = app.state::<AppState>();
+            // Forward manager events to all windows and keep the tray in sync with them
+            forward_events_to_windows(&app_handle, &manager)?;
+            subscribe_to_manager_events(&app_handle, &manager)?;
+            show_desktop_notifications(&app_handle, &manager)?;
+            sync_run_app_on_startup(&app_handle, &manager)?;
 
             //Start nodes that have automatic start option selected
-            run_nodes_on_startup(&state)?;
+            run_nodes_on_startup(&manager);
+
+            // Periodically probe running nodes and publish health changes
+            tauri::async_runtime::spawn(run_health_monitor(manager.clone()));
+
+            // Sample resource usage of the nodes run by the application
+            start_metrics_collector(manager.clone());
+
+            // Stop or restart nodes that keep exceeding their soft resource limits
+            tauri::async_runtime::spawn(run_watchdog(manager.clone()));
+
+            // Snapshot nodes on their backup schedule, catching up on runs missed while closed
+            tauri::async_runtime::spawn(run_backup_scheduler(manager.clone()));
+
+            // Permanently delete trashed nodes past the retention period
+            start_trash_purger(manager.clone());
+
+            // Let scripts drive the manager over the loopback interface if enabled
+            tauri::async_runtime::spawn(run_control_api(manager.clone()));
 
-            update_tray_menu(app.state())?;
+            update_tray_menu(&app_handle, &manager)?;
             Ok(())
         })
         .on_menu_event(|event| {
             if let Err(e) = handle_menu_click(&event) {
-                eprintln!("Error handling menu click: {:?}", e);
+                log::error!(operation = "menu_click"; "Error handling menu click: {:?}", e);
             }
         })
         .system_tray(system_tray)
@@ -68,7 +88,10 @@ fn main() -> eyre::Result<()> {
                     let app_handle_clone = app_handle.clone();
                     tauri::async_runtime::spawn(async move {
                         if let Err(e) = handle_tray_click(&app_handle_clone, &id) {
-                            eprintln!("Error handling tray click: {:?}", e);
+                            log::error!(
+                                operation = "tray_click";
+                                "Error handling tray click {}: {:?}", id, e
+                            );
                         }
                     });
                 }
@@ -94,6 +117,45 @@ fn main() -> eyre::Result<()> {
             commands::get_node_log,
             commands::delete_node,
             commands::open_dashboard,
+            commands::fetch_node_groups,
+            commands::save_node_group,
+            commands::delete_node_group,
+            commands::start_node_group,
+            commands::stop_node_group,
+            commands::restart_node_group,
+            commands::fetch_node_metrics,
+            commands::fetch_settings,
+            commands::save_settings,
+            commands::backup_node,
+            commands::restore_node,
+            commands::create_diagnostic_bundle,
+            commands::fetch_trash,
+            commands::restore_trashed_node,
+            commands::import_node,
+            commands::fetch_workspaces,
+            commands::save_workspace,
+            commands::delete_workspace,
+            commands::switch_workspace,
+            commands::check_node_admin_health,
+            commands::fetch_node_applications,
+            commands::install_node_application,
+            commands::install_node_dev_application,
+            commands::fetch_node_contexts,
+            commands::create_node_context,
+            commands::delete_node_context,
+            commands::invite_to_node_context,
+            commands::join_node_context,
+            commands::fetch_node_context_identities,
+            commands::generate_node_context_identity,
+            commands::send_node_rpc_request,
+            commands::replay_node_rpc_request,
+            commands::fetch_node_rpc_history,
+            commands::clear_node_rpc_history,
+            commands::subscribe_node_contexts,
+            commands::unsubscribe_node_contexts,
+            commands::fetch_subscriptions,
+            commands::fetch_node_context_events,
+            commands::fetch_app_log,
         ])
         .run(tauri::generate_context!())?;
 
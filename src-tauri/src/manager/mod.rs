@@ -0,0 +1,160 @@
+use crate::{
+    events::EventBus,
+    logger::get_node_log_file,
+    redaction::{refresh_redactor, Redactor},
+    runtime::NodeRuntime,
+    store::{migrate_settings, SettingsStore},
+    types::{
+        ContextEvent, NodeHealth, NodeMetricsSample, NodeProcess, NodeSubscription, RpcHistoryEntry,
+    },
+    utils::{default_nodes_dir, get_nodes_dir},
+};
+use eyre::{eyre, Result};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// Bundle identifier of the desktop app, which names its data directory
+pub const APP_IDENTIFIER: &str = "calimero.node.manager";
+const STORE_FILE: &str = "node_manager.dat";
+
+#[derive(Debug, Clone)]
+pub struct ManagerConfig {
+    // Directory holding the settings store and the default nodes, backups and trash directories
+    pub data_dir: PathBuf,
+    // How meroctl is launched to initialize and run the nodes
+    pub runtime: NodeRuntime,
+}
+
+// The node management service shared by the desktop app and the headless CLI. It is
+// cheap to clone, all clones share the same nodes, settings and event subscribers.
+#[derive(Clone)]
+pub struct NodeManager {
+    pub config: Arc<ManagerConfig>,
+    pub store: Arc<Mutex<SettingsStore>>,
+    pub nodes: Arc<Mutex<HashMap<String, NodeProcess>>>,
+    pub health: Arc<Mutex<HashMap<String, NodeHealth>>>,
+    pub metrics: Arc<Mutex<HashMap<String, VecDeque<NodeMetricsSample>>>>,
+    pub rpc_history: Arc<Mutex<HashMap<String, VecDeque<RpcHistoryEntry>>>>,
+    pub subscriptions: Arc<Mutex<HashMap<String, NodeSubscription>>>,
+    pub context_events: Arc<Mutex<HashMap<String, VecDeque<ContextEvent>>>>,
+    pub redactor: Arc<Mutex<Redactor>>,
+    pub events: EventBus,
+}
+
+impl NodeManager {
+    // Open the settings store in the data directory and load the nodes of the active workspace
+    pub fn open(config: ManagerConfig) -> Result<Self> {
+        fs::create_dir_all(&config.data_dir)
+            .map_err(|e| eyre!("Failed to create app data directory: {}", e))?;
+
+        let mut store = SettingsStore::open(
+            config.data_dir.join(STORE_FILE),
+            default_nodes_dir(&config.data_dir),
+        )?;
+        migrate_settings(&mut store)?;
+
+        let manager = NodeManager {
+            config: Arc::new(config),
+            store: Arc::new(Mutex::new(store)),
+            nodes: Arc::new(Mutex::new(HashMap::new())),
+            health: Arc::new(Mutex::new(HashMap::new())),
+            metrics: Arc::new(Mutex::new(HashMap::new())),
+            rpc_history: Arc::new(Mutex::new(HashMap::new())),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            context_events: Arc::new(Mutex::new(HashMap::new())),
+            redactor: Arc::new(Mutex::new(Redactor::default())),
+            events: EventBus::default(),
+        };
+        refresh_redactor(&manager)?;
+        setup_app_folders(&manager)?;
+        load_workspace_nodes(&manager)?;
+
+        Ok(manager)
+    }
+}
+
+// Data directory of the desktop app, e.g. ~/.local/share/calimero.node.manager on Linux
+pub fn default_data_dir() -> Result<PathBuf> {
+    dirs_next::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| eyre!("Failed to get app data dir"))
+}
+
+// Log directory of the desktop app, e.g. ~/.config/calimero.node.manager/logs on Linux
+pub fn default_log_dir() -> Result<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let dir = dirs_next::home_dir().map(|dir| dir.join("Library/Logs").join(APP_IDENTIFIER));
+    #[cfg(not(target_os = "macos"))]
+    let dir = dirs_next::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join("logs"));
+    dir.ok_or_else(|| eyre!("Failed to get app log dir"))
+}
+
+// Diagnostic bundles, restores and imports copy or compress whole node directories, so
+// the commands and the control API run them off the async runtime. Backups do so on their own
+pub async fn run_blocking<T, F>(manager: &NodeManager, operation: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&NodeManager) -> Result<T> + Send + 'static,
+{
+    let manager = manager.clone();
+    tokio::task::spawn_blocking(move || operation(&manager))
+        .await
+        .map_err(|e| eyre!("Blocking task failed: {}", e))
+        .and_then(|result| result)
+}
+
+pub fn setup_app_folders(manager: &NodeManager) -> Result<()> {
+    let nodes_dir = get_nodes_dir(manager);
+    if !nodes_dir.exists() {
+        fs::create_dir_all(nodes_dir)
+            .map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
+    }
+    Ok(())
+}
+
+// Replace the managed nodes with the ones in the active workspace
+pub fn load_workspace_nodes(manager: &NodeManager) -> Result<()> {
+    let loaded = load_nodes(manager)?;
+    let mut nodes = manager
+        .nodes
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+    *nodes = loaded;
+    Ok(())
+}
+
+fn load_nodes(manager: &NodeManager) -> Result<HashMap<String, NodeProcess>> {
+    let mut nodes = HashMap::new();
+
+    let nodes_dir = get_nodes_dir(manager);
+    if !nodes_dir.exists() {
+        return Ok(HashMap::new());
+    }
+
+    // Iterate over the directory entries
+    for entry in fs::read_dir(nodes_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        // Check if the entry is a directory
+        if path.is_dir() {
+            if let Some(node_name) = path.file_name().and_then(|name| name.to_str()) {
+                let log_file = get_node_log_file(manager, node_name)?;
+
+                nodes.insert(
+                    node_name.to_string(),
+                    NodeProcess {
+                        process: None,
+                        stdin: None,
+                        output: Arc::new(Mutex::new(String::new())),
+                        log_file: Some(log_file),
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(nodes)
+}
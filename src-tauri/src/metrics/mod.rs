@@ -0,0 +1,202 @@
+use crate::{
+    events::ManagerEvent,
+    manager::NodeManager,
+    types::NodeMetricsSample,
+    utils::{dir_size, format_bytes, get_nodes_dir},
+};
+use chrono::Local;
+use eyre::{eyre, Result};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+// Walking a data directory is much more expensive than reading process stats
+const DATA_DIR_SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+// Keep the last 10 minutes of samples per node
+pub const MAX_SAMPLES: usize = 120;
+
+pub fn get_node_metrics(manager: &NodeManager, node_name: &str) -> Result<Vec<NodeMetricsSample>> {
+    let metrics = manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?;
+    Ok(metrics
+        .get(node_name)
+        .map(|samples| samples.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+pub fn format_metrics_summary(sample: &NodeMetricsSample) -> String {
+    format!(
+        "CPU {:.1}% · RAM {}",
+        sample.cpu_percent,
+        format_bytes(sample.memory_bytes)
+    )
+}
+
+// Latest metrics summary for every node that has samples, used by the tray
+pub fn latest_metrics_summaries(manager: &NodeManager) -> Result<HashMap<String, String>> {
+    let metrics = manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?;
+    Ok(metrics
+        .iter()
+        .filter_map(|(node_name, samples)| {
+            samples
+                .back()
+                .map(|sample| (node_name.clone(), format_metrics_summary(sample)))
+        })
+        .collect())
+}
+
+pub fn push_sample(
+    manager: &NodeManager,
+    node_name: &str,
+    sample: NodeMetricsSample,
+) -> Result<()> {
+    let mut metrics = manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?;
+    let samples = metrics
+        .entry(node_name.to_string())
+        .or_insert_with(VecDeque::new);
+    if samples.len() == MAX_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+    Ok(())
+}
+
+// Drop the samples of a node that stopped or was deleted
+pub fn remove_node_metrics(manager: &NodeManager, node_name: &str) -> Result<()> {
+    manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?
+        .remove(node_name);
+    Ok(())
+}
+
+pub fn rename_node_metrics(manager: &NodeManager, old_name: &str, new_name: &str) -> Result<()> {
+    let mut metrics = manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?;
+    metrics.remove(new_name);
+    if let Some(samples) = metrics.remove(old_name) {
+        metrics.insert(new_name.to_string(), samples);
+    }
+    Ok(())
+}
+
+// Process IDs of the nodes spawned by the application that are still alive
+fn running_node_pids(manager: &NodeManager) -> Result<Vec<(String, Pid)>> {
+    let mut nodes = manager
+        .nodes
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+    Ok(nodes
+        .iter_mut()
+        .filter_map(|(node_name, node_process)| {
+            let process = node_process.process.as_mut()?;
+            match process.try_wait() {
+                Ok(None) => Some((node_name.clone(), Pid::from_u32(process.id()))),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn count_open_files(pid: Pid) -> Option<u64> {
+    fs::read_dir(format!("/proc/{}/fd", pid.as_u32()))
+        .ok()
+        .map(|entries| entries.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_open_files(_pid: Pid) -> Option<u64> {
+    None
+}
+
+// Data directory size of a node, recomputed once per `DATA_DIR_SAMPLE_INTERVAL`
+fn data_dir_size(
+    data_dir_sizes: &mut HashMap<String, (Instant, u64)>,
+    node_dir: &Path,
+    node_name: &str,
+) -> u64 {
+    match data_dir_sizes.get(node_name) {
+        Some((sampled_at, size)) if sampled_at.elapsed() < DATA_DIR_SAMPLE_INTERVAL => *size,
+        _ => {
+            let size = dir_size(node_dir);
+            data_dir_sizes.insert(node_name.to_string(), (Instant::now(), size));
+            size
+        }
+    }
+}
+
+fn collect_metrics(
+    manager: &NodeManager,
+    system: &mut System,
+    data_dir_sizes: &mut HashMap<String, (Instant, u64)>,
+) -> Result<()> {
+    let nodes = running_node_pids(manager)?;
+    // Forget the sizes of stopped nodes so a restarted or renamed node is measured again
+    data_dir_sizes.retain(|node_name, _| nodes.iter().any(|(name, _)| name == node_name));
+    if nodes.is_empty() {
+        return Ok(());
+    }
+
+    let pids: Vec<Pid> = nodes.iter().map(|(_, pid)| *pid).collect();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        ProcessRefreshKind::new()
+            .with_cpu()
+            .with_memory()
+            .with_disk_usage(),
+    );
+
+    let nodes_dir = get_nodes_dir(manager);
+    let timestamp = Local::now().timestamp_millis();
+    for (node_name, pid) in nodes {
+        let Some(process) = system.process(pid) else {
+            continue;
+        };
+        let disk_usage = process.disk_usage();
+        let sample = NodeMetricsSample {
+            timestamp,
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            disk_read_bytes: disk_usage.read_bytes,
+            disk_written_bytes: disk_usage.written_bytes,
+            data_dir_bytes: data_dir_size(data_dir_sizes, &nodes_dir.join(&node_name), &node_name),
+            open_files: count_open_files(pid),
+        };
+
+        push_sample(manager, &node_name, sample.clone())?;
+        manager
+            .events
+            .publish(ManagerEvent::Metrics { node_name, sample })?;
+    }
+
+    Ok(())
+}
+
+pub fn start_metrics_collector(manager: NodeManager) {
+    std::thread::spawn(move || {
+        // The system is kept between samples so CPU usage can be computed from the delta
+        let mut system = System::new();
+        let mut data_dir_sizes = HashMap::new();
+        loop {
+            if let Err(e) = collect_metrics(&manager, &mut system, &mut data_dir_sizes) {
+                log::error!(operation = "collect_metrics"; "Error collecting node metrics: {:?}", e);
+            }
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+    });
+}
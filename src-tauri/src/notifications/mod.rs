@@ -0,0 +1,124 @@
+use crate::{
+    error::ManagerError,
+    events::ManagerEvent,
+    manager::NodeManager,
+    store::get_settings,
+    types::{HealthStatus, NodeEvent, NotificationKind},
+};
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub node_name: String,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+// The notification a manager event raises, if any
+pub fn notification_for_event(event: &ManagerEvent) -> Option<Notification> {
+    let (node_name, kind, title, body) = match event {
+        ManagerEvent::Node(NodeEvent::Exited {
+            node_name,
+            exit_code,
+        }) => (
+            node_name,
+            NotificationKind::NodeExited,
+            format!("Node {} crashed", node_name),
+            match exit_code {
+                Some(code) => format!("The node exited unexpectedly with code {}", code),
+                None => "The node was killed by a signal".to_string(),
+            },
+        ),
+        ManagerEvent::Node(NodeEvent::StartFailed {
+            node_name,
+            error: error @ ManagerError::PortInUse { .. },
+        }) => (
+            node_name,
+            NotificationKind::PortConflict,
+            format!("Node {} failed to start", node_name),
+            error.to_string(),
+        ),
+        ManagerEvent::HealthChanged(event)
+            if matches!(
+                event.status,
+                HealthStatus::Degraded | HealthStatus::Unreachable
+            ) && matches!(
+                event.previous,
+                HealthStatus::Starting | HealthStatus::Healthy
+            ) =>
+        {
+            (
+                &event.node_name,
+                NotificationKind::Unhealthy,
+                format!("Node {} is unhealthy", event.node_name),
+                match event.status {
+                    HealthStatus::Degraded => "The node is running but not fully reachable",
+                    _ => "The node is not responding",
+                }
+                .to_string(),
+            )
+        }
+        _ => return None,
+    };
+    Some(Notification {
+        node_name: node_name.clone(),
+        kind,
+        title,
+        body,
+    })
+}
+
+// Minimum interval between notifications like this one, or None if the settings
+// turn it off. The node's own selection of kinds takes precedence over the app's.
+fn notification_interval(
+    manager: &NodeManager,
+    notification: &Notification,
+) -> Result<Option<Duration>> {
+    let settings = get_settings(manager)?;
+    let app = settings.app.notifications.clone();
+    let kinds = settings
+        .workspace()?
+        .nodes
+        .get(&notification.node_name)
+        .and_then(|node| node.notifications.clone())
+        .unwrap_or(app.kinds);
+    if !app.enabled || !kinds.contains(&notification.kind) {
+        return Ok(None);
+    }
+    Ok(Some(Duration::from_secs(app.min_interval_secs)))
+}
+
+// Call notify for the notifications raised by manager events, allowing one
+// notification of each kind per node within the configured interval
+pub fn subscribe_notifications(
+    manager: &NodeManager,
+    notify: impl Fn(&Notification) + Send + Sync + 'static,
+) -> Result<()> {
+    let subscriber = manager.clone();
+    let last_sent: Mutex<HashMap<(String, NotificationKind), Instant>> = Mutex::new(HashMap::new());
+    manager.events.subscribe(move |event| {
+        let Some(notification) = notification_for_event(event) else {
+            return;
+        };
+        let Ok(Some(interval)) = notification_interval(&subscriber, &notification) else {
+            return;
+        };
+        let Ok(mut last_sent) = last_sent.lock() else {
+            return;
+        };
+        let key = (notification.node_name.clone(), notification.kind);
+        if last_sent
+            .get(&key)
+            .is_some_and(|sent_at| sent_at.elapsed() < interval)
+        {
+            return;
+        }
+        last_sent.insert(key, Instant::now());
+        drop(last_sent);
+        notify(&notification);
+    })
+}
@@ -0,0 +1,277 @@
+use crate::{
+    events::ManagerEvent,
+    health::{admin_api_health_url, probe_admin_api},
+    manager::NodeManager,
+    operations::{start_node, stop_node_process},
+    store::{get_node_groups, save_node_groups},
+    types::NodeGroup,
+    utils::{get_node_ports, get_nodes_dir, is_node_process_running},
+};
+use eyre::{bail, eyre, Result};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Resolve the order in which the group members should be started,
+// dependencies first and otherwise in declaration order
+pub fn resolve_start_order(group: &NodeGroup) -> Result<Vec<String>> {
+    let mut order = Vec::new();
+    let mut visiting = Vec::new();
+    for member in &group.members {
+        visit_member(group, &member.node_name, &mut visiting, &mut order)?;
+    }
+    Ok(order)
+}
+
+fn visit_member(
+    group: &NodeGroup,
+    node_name: &str,
+    visiting: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<()> {
+    if order.iter().any(|n| n == node_name) {
+        return Ok(());
+    }
+    if visiting.iter().any(|n| n == node_name) {
+        bail!(
+            "Dependency cycle detected in group {}: {} -> {}",
+            group.name,
+            visiting.join(" -> "),
+            node_name
+        );
+    }
+
+    let member = group
+        .member(node_name)
+        .ok_or_else(|| eyre!("Node {} is not a member of group {}", node_name, group.name))?;
+
+    visiting.push(node_name.to_string());
+    for dependency in &member.depends_on {
+        visit_member(group, dependency, visiting, order)?;
+    }
+    visiting.pop();
+
+    order.push(node_name.to_string());
+    Ok(())
+}
+
+// Order the given nodes so that group members come first in their group start order,
+// followed by the remaining nodes sorted by name
+pub fn order_nodes(groups: &[NodeGroup], node_names: &[String]) -> Vec<(String, bool)> {
+    let mut ordered: Vec<(String, bool)> = Vec::new();
+
+    for group in groups {
+        let Ok(group_order) = resolve_start_order(group) else {
+            continue;
+        };
+        for node_name in group_order {
+            if node_names.contains(&node_name) && !ordered.iter().any(|(n, _)| *n == node_name) {
+                let wait_until_ready = group
+                    .member(&node_name)
+                    .map(|m| m.wait_until_ready)
+                    .unwrap_or(false);
+                ordered.push((node_name, wait_until_ready));
+            }
+        }
+    }
+
+    let mut remaining: Vec<String> = node_names
+        .iter()
+        .filter(|name| !ordered.iter().any(|(n, _)| n == *name))
+        .cloned()
+        .collect();
+    remaining.sort();
+    ordered.extend(remaining.into_iter().map(|name| (name, false)));
+
+    ordered
+}
+
+// Check the group is well formed, independently of which nodes currently exist
+pub fn validate_group_structure(group: &NodeGroup) -> Result<()> {
+    if group.name.trim().is_empty() {
+        bail!("Group name cannot be empty");
+    }
+    if group.members.is_empty() {
+        bail!("Group {} must contain at least one node", group.name);
+    }
+
+    let mut seen = HashSet::new();
+    for member in &group.members {
+        if !seen.insert(member.node_name.as_str()) {
+            bail!(
+                "Node {} is listed more than once in group {}",
+                member.node_name,
+                group.name
+            );
+        }
+        for dependency in &member.depends_on {
+            if group.member(dependency).is_none() {
+                bail!(
+                    "Node {} depends on {}, which is not a member of group {}",
+                    member.node_name,
+                    dependency,
+                    group.name
+                );
+            }
+        }
+    }
+
+    resolve_start_order(group)?;
+    Ok(())
+}
+
+fn validate_group(manager: &NodeManager, group: &NodeGroup) -> Result<()> {
+    validate_group_structure(group)?;
+
+    let nodes_dir = get_nodes_dir(manager);
+    for member in &group.members {
+        if !nodes_dir.join(&member.node_name).exists() {
+            bail!("Node {} does not exist", member.node_name);
+        }
+    }
+    Ok(())
+}
+
+fn find_group(manager: &NodeManager, group_name: &str) -> Result<NodeGroup> {
+    get_node_groups(manager)?
+        .into_iter()
+        .find(|g| g.name == group_name)
+        .ok_or_else(|| eyre!("Group not found: {}", group_name))
+}
+
+pub fn save_group(manager: &NodeManager, group: NodeGroup) -> Result<()> {
+    validate_group(manager, &group)?;
+
+    let mut groups = get_node_groups(manager)?;
+    match groups.iter_mut().find(|g| g.name == group.name) {
+        Some(existing) => *existing = group,
+        None => groups.push(group),
+    }
+    save_node_groups(manager, &groups)?;
+
+    manager.events.publish(ManagerEvent::GroupsChanged)?;
+
+    Ok(())
+}
+
+pub fn delete_group(manager: &NodeManager, group_name: String) -> Result<()> {
+    let mut groups = get_node_groups(manager)?;
+    let len = groups.len();
+    groups.retain(|g| g.name != group_name);
+    if groups.len() == len {
+        return Err(eyre!("Group not found: {}", group_name));
+    }
+    save_node_groups(manager, &groups)?;
+
+    manager.events.publish(ManagerEvent::GroupsChanged)?;
+
+    Ok(())
+}
+
+// Keep group membership in sync when a node is renamed
+pub fn rename_group_member(
+    manager: &NodeManager,
+    original_node_name: &str,
+    node_name: &str,
+) -> Result<()> {
+    let mut groups = get_node_groups(manager)?;
+    let mut changed = false;
+    for group in groups.iter_mut() {
+        for member in group.members.iter_mut() {
+            if member.node_name == original_node_name {
+                member.node_name = node_name.to_string();
+                changed = true;
+            }
+            for dependency in member.depends_on.iter_mut() {
+                if dependency == original_node_name {
+                    *dependency = node_name.to_string();
+                    changed = true;
+                }
+            }
+        }
+    }
+    if changed {
+        save_node_groups(manager, &groups)?;
+    }
+    Ok(())
+}
+
+// Drop a deleted node from every group, together with any dependencies on it
+pub fn remove_group_member(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let mut groups = get_node_groups(manager)?;
+    let mut changed = false;
+    for group in groups.iter_mut() {
+        let len = group.members.len();
+        group.members.retain(|m| m.node_name != node_name);
+        changed |= group.members.len() != len;
+        for member in group.members.iter_mut() {
+            let len = member.depends_on.len();
+            member.depends_on.retain(|d| d != node_name);
+            changed |= member.depends_on.len() != len;
+        }
+    }
+    if changed {
+        groups.retain(|g| !g.members.is_empty());
+        save_node_groups(manager, &groups)?;
+    }
+    Ok(())
+}
+
+// Wait until the node's admin API answers its health endpoint
+pub async fn wait_for_node_ready(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let config = get_node_ports(node_name, manager)?;
+    let health_url = admin_api_health_url(config.server_port);
+    let deadline = Instant::now() + READY_TIMEOUT;
+
+    while !probe_admin_api(&health_url).await {
+        if Instant::now() >= deadline {
+            bail!(
+                "Node {} did not become ready within {} seconds",
+                node_name,
+                READY_TIMEOUT.as_secs()
+            );
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+pub async fn start_group(manager: &NodeManager, group_name: String) -> Result<()> {
+    let group = find_group(manager, &group_name)?;
+
+    for node_name in resolve_start_order(&group)? {
+        if !is_node_process_running(manager, &node_name)? {
+            start_node(manager, node_name.clone()).await?;
+        }
+
+        let wait_until_ready = group
+            .member(&node_name)
+            .map(|m| m.wait_until_ready)
+            .unwrap_or(false);
+        if wait_until_ready {
+            wait_for_node_ready(manager, &node_name).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn stop_group(manager: &NodeManager, group_name: String) -> Result<()> {
+    let group = find_group(manager, &group_name)?;
+
+    // Stop dependents before the nodes they depend on
+    for node_name in resolve_start_order(&group)?.into_iter().rev() {
+        if is_node_process_running(manager, &node_name)? {
+            stop_node_process(manager, node_name).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn restart_group(manager: &NodeManager, group_name: String) -> Result<()> {
+    stop_group(manager, group_name.clone()).await?;
+    start_group(manager, group_name).await
+}
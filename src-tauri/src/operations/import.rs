@@ -0,0 +1,123 @@
+use crate::{
+    error::ManagerError,
+    logger::create_log_file,
+    manager::NodeManager,
+    operations::write_node_ports,
+    types::{ImportMode, NodeConfig, NodeEvent, NodeProcess},
+    utils::{copy_dir_all, get_assigned_ports, get_nodes_dir, read_node_ports, validate_node_name},
+};
+use eyre::{bail, eyre, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// Check the directory is a meroctl node home and return the ports from its config
+pub fn validate_node_home(source: &Path) -> Result<NodeConfig> {
+    if !source.is_dir() {
+        bail!("{} is not a directory", source.display());
+    }
+    if !source.join("config.toml").is_file() {
+        bail!(
+            "{} is not a node directory, config.toml is missing",
+            source.display()
+        );
+    }
+    read_node_ports(source).map_err(|e| eyre!("Invalid node config in {}: {}", source.display(), e))
+}
+
+#[cfg(unix)]
+fn link_node_dir(source: &Path, node_dir: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, node_dir)
+}
+
+#[cfg(windows)]
+fn link_node_dir(source: &Path, node_dir: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(source, node_dir)
+}
+
+// Bring a node directory created outside the application, e.g. with the meroctl CLI
+// under ~/.calimero, under management. Ports can be overridden to resolve conflicts.
+pub fn import_node(
+    manager: &NodeManager,
+    source: PathBuf,
+    node_name: Option<String>,
+    mode: ImportMode,
+    server_port: Option<u32>,
+    swarm_port: Option<u32>,
+) -> Result<String> {
+    let source = source
+        .canonicalize()
+        .map_err(|e| eyre!("Failed to resolve {}: {}", source.display(), e))?;
+    let config = validate_node_home(&source)?;
+
+    let node_name = match node_name {
+        Some(node_name) => node_name,
+        None => source
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+            .ok_or_else(|| eyre!("Failed to determine node name from {}", source.display()))?,
+    };
+    validate_node_name(&node_name)?;
+
+    let nodes_dir = get_nodes_dir(manager);
+    let node_dir = nodes_dir.join(&node_name);
+    if node_dir.exists() || node_dir.is_symlink() {
+        bail!(ManagerError::NodeAlreadyExists { node_name });
+    }
+    if source.starts_with(&nodes_dir) {
+        bail!("{} is already managed by the application", source.display());
+    }
+
+    let server_port = server_port.unwrap_or(config.server_port as u32);
+    let swarm_port = swarm_port.unwrap_or(config.swarm_port as u32);
+    if server_port == swarm_port {
+        bail!("Server and swarm ports must be different");
+    }
+    let assigned_ports = get_assigned_ports(manager)?;
+    for port in [server_port, swarm_port] {
+        if assigned_ports.contains(&(port as u16)) {
+            bail!(ManagerError::PortInUse { port: port as u16 });
+        }
+    }
+
+    fs::create_dir_all(&nodes_dir).map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
+    match mode {
+        ImportMode::Copy => {
+            if let Err(e) = copy_dir_all(&source, &node_dir) {
+                let _ = fs::remove_dir_all(&node_dir);
+                return Err(e);
+            }
+        }
+        ImportMode::Link => link_node_dir(&source, &node_dir)
+            .map_err(|e| eyre!("Failed to link node directory: {}", e))?,
+    }
+
+    if server_port != config.server_port as u32 || swarm_port != config.swarm_port as u32 {
+        write_node_ports(&node_dir, server_port, swarm_port)?;
+    }
+
+    let log_file = create_log_file(manager, &node_name)
+        .map_err(|e| eyre!("Failed to create log file: {}", e))?;
+    {
+        let mut nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes.insert(
+            node_name.clone(),
+            NodeProcess {
+                process: None,
+                stdin: None,
+                output: Arc::new(Mutex::new(String::new())),
+                log_file: Some(log_file),
+            },
+        );
+    }
+
+    manager.events.publish_node(NodeEvent::Created {
+        node_name: node_name.clone(),
+    })?;
+
+    Ok(node_name)
+}
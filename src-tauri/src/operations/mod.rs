@@ -1,53 +1,78 @@
 use crate::{
-    logger::{create_log_file, write_to_log},
-    store::{get_run_node_on_startup, update_run_node_on_startup},
-    tray::update_tray_menu,
-    types::{AppState, NodeInfo, NodeProcess},
+    error::ManagerError,
+    events::ManagerEvent,
+    health::{current_health, mark_node_started, mark_node_stopped},
+    limits::{
+        apply_resource_limits, attach_to_cgroup, limit_process_memory, prepare_cgroup,
+        remove_cgroup,
+    },
+    logger::{app::set_app_log_level, create_log_file, get_log_file_path, write_to_log},
+    manager::NodeManager,
+    metrics::{remove_node_metrics, rename_node_metrics},
+    operations::groups::{
+        order_nodes, remove_group_member, rename_group_member, wait_for_node_ready,
+    },
+    redaction::{redact, Redactor, REDACTED},
+    store::{
+        get_node_groups, get_node_launch_profile, get_node_resource_limits, get_node_settings,
+        get_run_node_on_startup, get_settings, remove_node_settings, rename_node_settings,
+        update_node_launch_profile, update_run_node_on_startup, update_settings,
+    },
+    trash::move_to_trash,
+    types::{LaunchProfile, NodeEvent, NodeInfo, NodeProcess, Settings},
     utils::{
-        check_ports_availability, get_binary_path, get_node_ports, get_nodes_dir,
-        is_node_process_running, is_port_in_use, kill_node_process, strip_ansi_escapes,
+        check_ports_availability, dashboard_url, get_node_ports, get_nodes_dir,
+        is_node_process_running, is_node_process_running_in, is_port_in_use, kill_node_process,
+        process_snapshot, read_node_peer_info, strip_ansi_escapes, validate_launch_profile,
     },
 };
 use chrono::Local;
-use eyre::{eyre, Result};
+use eyre::{bail, eyre, Result};
 use multiaddr::{Multiaddr, Protocol};
 use serde_json::Value;
 use std::io::BufRead;
 use std::io::Write;
 use std::{
+    collections::HashSet,
     fs,
     io::BufReader,
+    path::Path,
     process::{Command, Stdio},
     sync::{mpsc, Arc, Mutex},
 };
-use tauri::State;
-use tauri::{AppHandle, Manager};
+
+pub mod groups;
+pub mod import;
 
 pub async fn create_node(
-    state: State<'_, AppState>,
+    manager: &NodeManager,
     node_name: String,
     server_port: u32,
     swarm_port: u32,
     run_on_startup: bool,
-) -> Result<bool> {
-    let nodes_dir = get_nodes_dir(&state.app_handle);
+    launch_profile: LaunchProfile,
+) -> Result<()> {
+    validate_launch_profile(&launch_profile)?;
+
+    let nodes_dir = get_nodes_dir(manager);
     fs::create_dir_all(&nodes_dir).map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
+    if nodes_dir.join(&node_name).exists() {
+        bail!(ManagerError::NodeAlreadyExists { node_name });
+    }
 
-    let binary_path = get_binary_path(&state.app_handle)?;
-    let output = Command::new(binary_path)
-        .args(&[
-            "--node-name",
-            &node_name,
-            "--home",
-            nodes_dir
-                .to_str()
-                .ok_or_else(|| eyre!("Failed to convert path to string"))?,
+    let output = manager
+        .config
+        .runtime
+        .node_command(&node_name, &nodes_dir)?
+        .args([
             "init",
             "--server-port",
             &server_port.to_string(),
             "--swarm-port",
             &swarm_port.to_string(),
         ])
+        .args(&launch_profile.init_args)
+        .envs(&launch_profile.env)
         .output()
         .map_err(|e| eyre!("Failed to execute command: {}", e))?;
 
@@ -56,30 +81,33 @@ pub async fn create_node(
         return Err(eyre!("Failed to initialize node: {}", stderr));
     }
 
-    let mut log_file = create_log_file(&state.app_handle, &node_name)
+    let mut log_file = create_log_file(manager, &node_name)
         .map_err(|e| eyre!("Failed to create log file: {}", e))?;
     // Write stdout and stderr to log
     write_to_log(
+        manager,
         &mut log_file,
         &strip_ansi_escapes(&String::from_utf8_lossy(&output.stdout)),
     )
     .map_err(|e| eyre!("Failed to log node stdout: {}", e))?;
     write_to_log(
+        manager,
         &mut log_file,
         &strip_ansi_escapes(&String::from_utf8_lossy(&output.stderr)),
     )
     .map_err(|e| eyre!("Failed to log node stderr: {}", e))?;
 
-    update_run_node_on_startup(&state, &node_name, run_on_startup)?;
+    update_run_node_on_startup(manager, &node_name, run_on_startup)?;
+    update_node_launch_profile(manager, &node_name, &launch_profile)?;
 
     {
-        // Add the node to the AppState
-        let mut manager = state
-            .node_manager
+        // Add the node to the node manager
+        let mut nodes = manager
+            .nodes
             .lock()
             .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
 
-        manager.nodes.insert(
+        nodes.insert(
             node_name.clone(),
             NodeProcess {
                 process: None, // Not running initially
@@ -90,14 +118,18 @@ pub async fn create_node(
         );
     } // The mutable borrow ends here
 
-    // Call update_tray_menu after the mutable borrow is done
-    update_tray_menu(state)?;
+    // Publish after the mutable borrow is done
+    manager
+        .events
+        .publish_node(NodeEvent::Created { node_name })?;
 
-    Ok(true)
+    Ok(())
 }
 
-pub fn get_nodes(state: State<'_, AppState>) -> Result<Vec<NodeInfo>> {
-    let nodes_dir = get_nodes_dir(&state.app_handle);
+pub fn get_nodes(manager: &NodeManager) -> Result<Vec<NodeInfo>> {
+    let nodes_dir = get_nodes_dir(manager);
+    // One process table snapshot for every node, the tray rebuilds this list on each event
+    let system = process_snapshot();
     let mut nodes = Vec::new();
     for entry in
         fs::read_dir(nodes_dir).map_err(|e| eyre!("Failed to read nodes directory: {}", e))?
@@ -105,20 +137,24 @@ pub fn get_nodes(state: State<'_, AppState>) -> Result<Vec<NodeInfo>> {
         let entry = entry.map_err(|e| eyre!("Failed to read directory entry: {}", e))?;
         if let Some(node_name) = entry.file_name().to_str() {
             let node_name = node_name.to_owned();
-            if let Ok(config) = get_node_ports(&node_name, &state.app_handle) {
+            if let Ok(config) = get_node_ports(&node_name, manager) {
                 let (is_running, external_node) =
-                    match is_node_process_running(&state.app_handle, &node_name) {
+                    match is_node_process_running_in(&system, manager, &node_name) {
                         Ok(true) => (true, false),
                         Ok(false) => (false, false),
                         Err(_) => (false, true), // Assume running if there's an error
                     };
-                let run_on_startup = get_run_node_on_startup(&state, &node_name)?;
+                let run_on_startup = get_run_node_on_startup(manager, &node_name)?;
+                let health = current_health(manager, &node_name, is_running || external_node)?;
+                let peer_info = read_node_peer_info(&entry.path(), config.server_port)?;
                 nodes.push(NodeInfo {
                     name: node_name,
                     is_running,
                     run_on_startup,
                     node_ports: config,
                     external_node,
+                    health,
+                    peer_info,
                 });
             }
         }
@@ -128,27 +164,79 @@ pub fn get_nodes(state: State<'_, AppState>) -> Result<Vec<NodeInfo>> {
 }
 
 pub async fn update_node_config(
-    state: State<'_, AppState>,
+    manager: &NodeManager,
     original_node_name: String,
     node_name: String,
     server_port: u32,
     swarm_port: u32,
     run_on_startup: bool,
-) -> Result<bool> {
-    let nodes_dir = get_nodes_dir(&state.app_handle);
+) -> Result<()> {
+    let nodes_dir = get_nodes_dir(manager);
     let original_node_dir = nodes_dir.join(&original_node_name);
     let new_node_dir = nodes_dir.join(&node_name);
 
+    if !original_node_dir.exists() {
+        bail!(ManagerError::NodeNotFound {
+            node_name: original_node_name
+        });
+    }
+
     // Check if the new node name is already taken
     if original_node_name != node_name && new_node_dir.exists() {
-        return Err(eyre!(
-            "Cannot change node name, node with name {} already exists",
-            node_name
-        ));
+        bail!(ManagerError::NodeAlreadyExists { node_name });
     }
 
+    write_node_ports(&original_node_dir, server_port, swarm_port)?;
+
+    // Rename the node directory if the name has changed
+    if original_node_name != node_name {
+        let linked_log = original_node_dir
+            .is_symlink()
+            .then(|| get_log_file_path(manager, &original_node_name));
+        fs::rename(&original_node_dir, &new_node_dir)
+            .map_err(|e| eyre!("Failed to rename node directory: {}", e))?;
+        if let Some(linked_log) = linked_log {
+            fs::rename(&linked_log, get_log_file_path(manager, &node_name))
+                .map_err(|e| eyre!("Failed to rename log file: {}", e))?;
+        }
+        {
+            // The log file is renamed in place, so its handle moves along
+            let mut nodes = manager
+                .nodes
+                .lock()
+                .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+            if let Some(node_process) = nodes.remove(&original_node_name) {
+                nodes.insert(node_name.clone(), node_process);
+            }
+        }
+        rename_group_member(manager, &original_node_name, &node_name)?;
+        rename_node_settings(manager, &original_node_name, &node_name)?;
+        rename_node_metrics(manager, &original_node_name, &node_name)?;
+        manager.events.publish_node(NodeEvent::Renamed {
+            previous_name: original_node_name,
+            node_name: node_name.clone(),
+        })?;
+    }
+
+    update_run_node_on_startup(manager, &node_name, run_on_startup)
+        .map_err(|e| eyre!("Failed to update option to run node on startup: {}", e))?;
+    manager
+        .events
+        .publish_node(NodeEvent::ConfigChanged { node_name })?;
+
+    // let timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S.%6fZ");
+    // write_to_log(
+    //     &state.app_handle,
+    //     &format!("{} Node '{}' configuration updated successfully.", timestamp, node_name),
+    // )?;
+
+    Ok(())
+}
+
+// Rewrite the server and swarm listen ports in the node's config file
+pub fn write_node_ports(node_dir: &Path, server_port: u32, swarm_port: u32) -> Result<()> {
     // Read the config file
-    let config_path = original_node_dir.join("config.toml");
+    let config_path = node_dir.join("config.toml");
     let config_content =
         fs::read_to_string(&config_path).map_err(|e| eyre!("Failed to read config file: {}", e))?;
 
@@ -169,22 +257,7 @@ pub async fn update_node_config(
     fs::write(&config_path, updated_content)
         .map_err(|e| eyre!("Failed to write updated config: {}", e))?;
 
-    // Rename the node directory if the name has changed
-    if original_node_name != node_name {
-        fs::rename(&original_node_dir, &new_node_dir)
-            .map_err(|e| eyre!("Failed to rename node directory: {}", e))?;
-    }
-
-    update_run_node_on_startup(&state, &node_name, run_on_startup)
-        .map_err(|e| eyre!("Failed to update option to run node on startup: {}", e))?;
-
-    // let timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S.%6fZ");
-    // write_to_log(
-    //     &state.app_handle,
-    //     &format!("{} Node '{}' configuration updated successfully.", timestamp, node_name),
-    // )?;
-
-    Ok(true)
+    Ok(())
 }
 
 // Helper function to update port numbers
@@ -231,36 +304,72 @@ fn change_port_in_path(address: &str, new_port: &str) -> Result<String> {
     Ok(new_multiaddr.to_string())
 }
 
-// Start a node process
-pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result<bool> {
-    let app_handle = state.app_handle.clone();
-    let node_manager = state.node_manager.clone();
-    let mut manager = node_manager
-        .lock()
-        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+// Start a node process, publishing why if it could not be started
+pub async fn start_node(manager: &NodeManager, node_name: String) -> Result<()> {
+    let result = spawn_node(manager, node_name.clone()).await;
+    if let Err(e) = &result {
+        manager.events.publish_node(NodeEvent::StartFailed {
+            node_name,
+            error: ManagerError::from_report(e),
+        })?;
+    }
+    result
+}
 
-    let config = get_node_ports(&node_name, &app_handle)?;
+async fn spawn_node(manager: &NodeManager, node_name: String) -> Result<()> {
+    let config = get_node_ports(&node_name, manager)?;
     check_ports_availability(&config)?;
 
-    let nodes_dir = get_nodes_dir(&app_handle);
-    let binary_path = get_binary_path(&app_handle)?;
-
-    let mut process = Command::new(binary_path)
-        .args(&[
-            "--node-name",
-            &node_name,
-            "--home",
-            nodes_dir
-                .to_str()
-                .ok_or_else(|| eyre!("Failed to convert path to string".to_string()))?,
-            "run",
-        ])
+    let nodes_dir = get_nodes_dir(manager);
+
+    let limits = get_node_resource_limits(manager, &node_name)?;
+    let launch_profile = get_node_launch_profile(manager, &node_name)?;
+    let log_stdin = !get_node_settings(manager, &node_name)?.hide_stdin_in_log;
+    let cgroup = prepare_cgroup(&node_name, &limits);
+
+    let mut command = manager
+        .config
+        .runtime
+        .node_command(&node_name, &nodes_dir)?;
+    command
+        .arg("run")
+        .args(&launch_profile.run_args)
+        .envs(&launch_profile.env)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| eyre!("Failed to spawn node process: {}", e))?;
+        .stderr(Stdio::piped());
+    apply_resource_limits(&mut command, &limits, cgroup.is_none());
 
+    let mut process = command.spawn().map_err(|e| {
+        if let Some(cgroup) = &cgroup {
+            remove_cgroup(cgroup);
+        }
+        eyre!("Failed to spawn node process: {}", e)
+    })?;
+
+    // The rlimit fallback was skipped for the cgroup, so apply it now if attaching fails
+    let cgroup = match cgroup {
+        Some(cgroup) => match attach_to_cgroup(&cgroup, process.id()) {
+            Ok(()) => Some(cgroup),
+            Err(e) => {
+                log::warn!(
+                    node = node_name.as_str(), operation = "start_node";
+                    "Failed to attach node {} to its cgroup, limiting its data segment instead: {}",
+                    node_name, e
+                );
+                remove_cgroup(&cgroup);
+                if let Err(e) = limit_process_memory(process.id(), &limits) {
+                    let _ = process.kill();
+                    let _ = process.wait();
+                    bail!("Failed to apply memory limit for node {}: {}", node_name, e);
+                }
+                None
+            }
+        },
+        None => None,
+    };
+
+    let pid = process.id();
     let (tx, rx) = mpsc::channel::<String>();
     let output = Arc::new(Mutex::new(String::new()));
 
@@ -278,8 +387,12 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
         .ok_or_else(|| eyre!("Failed to capture stderr".to_string()))?;
 
     // Update the NodeManager with the new node process
-    let log_file = manager
+    let node_manager = manager.clone();
+    let mut nodes = manager
         .nodes
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+    let log_file = nodes
         .get_mut(&node_name)
         .and_then(|n| n.log_file.take())
         .ok_or_else(|| eyre!("Failed to get log file: {}", node_name))?;
@@ -295,17 +408,28 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
     // Spawn a thread to handle stdin
     std::thread::spawn({
         let node_name = node_name.clone();
+        let manager = node_manager.clone();
         let mut log_file = log_file_clone_for_stdin;
         move || {
             let mut stdin = stdin;
             for input in rx {
-                if let Err(e) = write_to_log(&mut log_file, &format!("STDIN: {}", input)) {
-                    eprintln!("Failed to log input for node {}: {}", node_name, e);
-                    return; // Exit the closure early if logging fails
+                if log_stdin {
+                    if let Err(e) =
+                        write_to_log(&manager, &mut log_file, &format!("STDIN: {}", input))
+                    {
+                        log::error!(
+                            node = node_name.as_str(), operation = "send_input";
+                            "Failed to log input for node {}: {}", node_name, e
+                        );
+                        return; // Exit the closure early if logging fails
+                    }
                 }
 
                 if writeln!(stdin, "{}", input).is_err() {
-                    eprintln!("Failed to write to stdin for node: {}", node_name);
+                    log::error!(
+                        node = node_name.as_str(), operation = "send_input";
+                        "Failed to write to stdin for node: {}", node_name
+                    );
                     break;
                 }
             }
@@ -316,7 +440,7 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
     std::thread::spawn({
         let output = Arc::clone(&output);
         let node_name = node_name.clone();
-        let app_handle = app_handle.clone();
+        let manager = node_manager;
         let mut log_file = log_file_clone_for_stdout; // Use the cloned log file
 
         move || {
@@ -325,7 +449,8 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
 
             let mut process_line = |line: std::io::Result<String>| -> Result<()> {
                 let l = line.map_err(|e| eyre!("Failed to read line: {}", e))?;
-                let cleaned_line = strip_ansi_escapes(&l);
+                // Redacted before it is buffered, logged or sent to node-output-* listeners
+                let cleaned_line = redact(&manager, &strip_ansi_escapes(&l))?;
 
                 {
                     let mut output_lock = output
@@ -334,22 +459,36 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
                     output_lock.push_str(&cleaned_line);
                     output_lock.push('\n');
                 }
-                write_to_log(&mut log_file, &cleaned_line)
+                write_to_log(&manager, &mut log_file, &cleaned_line)
                     .map_err(|e| eyre!("Failed to log output: {}", e))?;
-                // Ensure the emitted event name and data format are correct
-                app_handle
-                    .emit_all(&format!("node-output-{}", node_name), cleaned_line + "\n")
-                    .map_err(|e| eyre!("Failed to emit output for node {}: {}", node_name, e))?;
+                manager.events.publish(ManagerEvent::Output {
+                    node_name: node_name.clone(),
+                    line: cleaned_line,
+                })?;
 
                 Ok(())
             };
 
             for line in stdout_reader.lines().chain(stderr_reader.lines()) {
                 if let Err(e) = process_line(line) {
-                    eprintln!("Error processing line for node {}: {}", node_name, e);
+                    log::error!(
+                        node = node_name.as_str(), operation = "capture_output";
+                        "Error processing line for node {}: {}", node_name, e
+                    );
                     return Err(e);
                 }
             }
+
+            // The output ends when the process exits
+            if let Err(e) = handle_node_exit(&manager, &node_name, pid) {
+                log::error!(
+                    node = node_name.as_str(), operation = "node_exit";
+                    "Error handling exit of node {}: {:?}", node_name, e
+                );
+            }
+            if let Some(cgroup) = &cgroup {
+                remove_cgroup(cgroup);
+            }
             Ok(())
         }
     });
@@ -360,20 +499,58 @@ pub async fn start_node(state: State<'_, AppState>, node_name: String) -> Result
         output,
         log_file: Some(log_file), // Use the original log file here
     };
-    manager.nodes.insert(node_name.clone(), node_process);
+    nodes.insert(node_name.clone(), node_process);
+    drop(nodes);
 
-    update_tray_menu(state)?;
+    mark_node_started(manager, &node_name)?;
+    manager
+        .events
+        .publish_node(NodeEvent::Started { node_name })?;
 
-    Ok(true)
+    Ok(())
 }
 
-pub fn get_node_output(state: State<'_, AppState>, node_name: String) -> Result<String> {
-    let manager = state
-        .node_manager
+// Reap a node process that ended on its own. Nodes stopped through stop_node_process
+// have their process taken already, or replaced on restart, and are reported as stopped.
+fn handle_node_exit(manager: &NodeManager, node_name: &str, pid: u32) -> Result<()> {
+    let process = {
+        let mut nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        match nodes.get_mut(node_name) {
+            Some(node_process) if node_process.process.as_ref().map(|p| p.id()) == Some(pid) => {
+                node_process.stdin = None;
+                node_process.process.take()
+            }
+            _ => None,
+        }
+    };
+    let Some(mut process) = process else {
+        return Ok(());
+    };
+
+    let status = process
+        .wait()
+        .map_err(|e| eyre!("Failed to wait for node process: {}", e))?;
+
+    mark_node_stopped(manager, node_name)?;
+    remove_node_metrics(manager, node_name)?;
+    manager.events.publish_node(NodeEvent::Exited {
+        node_name: node_name.to_string(),
+        exit_code: status.code(),
+    })?;
+
+    Ok(())
+}
+
+pub fn get_node_output(manager: &NodeManager, node_name: String) -> Result<String> {
+    let nodes = manager
+        .nodes
         .lock()
         .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
 
-    match manager.nodes.get(&node_name) {
+    match nodes.get(&node_name) {
         Some(node_process) => {
             let output = node_process
                 .output
@@ -383,21 +560,21 @@ pub fn get_node_output(state: State<'_, AppState>, node_name: String) -> Result<
 
             Ok(output)
         }
-        None => Err(eyre!("Node not found: {}", node_name)),
+        None => Err(ManagerError::NodeNotFound { node_name }.into()),
     }
 }
 
-pub async fn stop_node_process(state: State<'_, AppState>, node_name: String) -> Result<bool> {
-    if !is_node_process_running(&state.app_handle, &node_name)? {
-        return Ok(false);
+pub async fn stop_node_process(manager: &NodeManager, node_name: String) -> Result<()> {
+    if !is_node_process_running(manager, &node_name)? {
+        bail!(ManagerError::NodeNotRunning { node_name });
     }
 
     let node_stopped = 'done: {
-        let mut manager = state
-            .node_manager
+        let mut nodes = manager
+            .nodes
             .lock()
             .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
-        if let Some(node_process) = manager.nodes.get_mut(&node_name) {
+        if let Some(node_process) = nodes.get_mut(&node_name) {
             if let Some(mut process) = node_process.process.take() {
                 break 'done process.kill().is_ok();
             }
@@ -406,15 +583,15 @@ pub async fn stop_node_process(state: State<'_, AppState>, node_name: String) ->
     };
 
     if !node_stopped {
-        kill_node_process(&node_name).map_err(|e| eyre!("Failed to stop node: {}", e))?;
+        kill_node_process(manager, &node_name).map_err(|e| eyre!("Failed to stop node: {}", e))?;
     }
 
     {
-        let mut manager = state
-            .node_manager
+        let mut nodes = manager
+            .nodes
             .lock()
             .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
-        if let Some(node_process) = manager.nodes.get_mut(&node_name) {
+        if let Some(node_process) = nodes.get_mut(&node_name) {
             // Set process, stdin, and output to None
             node_process.process = None;
             node_process.stdin = None;
@@ -423,6 +600,7 @@ pub async fn stop_node_process(state: State<'_, AppState>, node_name: String) ->
             if let Some(log_file) = node_process.log_file.as_mut() {
                 let timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S.%6fZ");
                 write_to_log(
+                    manager,
                     log_file,
                     &format!(
                         "{} Node '{}' has been stopped successfully.",
@@ -434,96 +612,87 @@ pub async fn stop_node_process(state: State<'_, AppState>, node_name: String) ->
         }
     } // The mutable borrow ends here
 
-    // Call update_tray_menu after the mutable borrow is done
-    update_tray_menu(state)?;
+    mark_node_stopped(manager, &node_name)?;
+    remove_node_metrics(manager, &node_name)?;
+
+    // Publish after the mutable borrow is done
+    manager
+        .events
+        .publish_node(NodeEvent::Stopped { node_name })?;
 
-    Ok(true)
+    Ok(())
 }
 
-pub fn send_input_to_node(
-    node_name: String,
-    input: String,
-    state: State<'_, AppState>,
-) -> Result<bool> {
-    let manager = state
-        .node_manager
+pub fn send_input_to_node(node_name: String, input: String, manager: &NodeManager) -> Result<()> {
+    // The echo is kept out of the output like the input is kept out of the log
+    let echo = if get_node_settings(manager, &node_name)?.hide_stdin_in_log {
+        REDACTED.to_string()
+    } else {
+        redact(manager, &input)?
+    };
+
+    let nodes = manager
+        .nodes
         .lock()
         .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
-    let node_process = manager
-        .nodes
+    let node_process = nodes
         .get(&node_name)
-        .ok_or_else(|| eyre!("Node not found: {}", node_name))?;
+        .ok_or_else(|| ManagerError::NodeNotFound {
+            node_name: node_name.clone(),
+        })?;
 
     let stdin = node_process
         .stdin
         .as_ref()
-        .ok_or_else(|| eyre!("Node is not running: {}", node_name))?;
+        .ok_or_else(|| ManagerError::NodeNotRunning {
+            node_name: node_name.clone(),
+        })?;
 
     // Add the input to the node's output
     {
-        let mut output = node_process.output
+        let mut output = node_process
+            .output
             .lock()
             .map_err(|e| eyre!("Failed to lock output: {}", e))?;
-        output.push_str(&format!("> {}\n", input));
+        output.push_str(&format!("> {}\n", echo));
     }
 
     stdin
         .send(input)
         .map_err(|e| eyre!("Failed to send input: {}", e))?;
-    Ok(true)
+    Ok(())
 }
 
-pub async fn delete_node(state: State<'_, AppState>, node_name: String) -> Result<bool> {
-    let nodes_dir = get_nodes_dir(&state.app_handle);
-    let node_dir = nodes_dir.join(&node_name);
-
-    if !node_dir.exists() {
-        return Ok(false);
-    }
-
-    // Ensure the node is not running
-    if is_node_process_running(&state.app_handle, &node_name)? {
-        return Ok(false);
-    }
+pub async fn delete_node(manager: &NodeManager, node_name: String) -> Result<()> {
+    // Move the node directory to the trash, fails if it is missing or still running
+    move_to_trash(manager, &node_name)?;
 
-    // Delete the node directory
-    fs::remove_dir_all(&node_dir).map_err(|e| eyre!("Failed to delete node directory: {}", e))?;
+    // Remove the node settings if present
+    remove_node_settings(manager, &node_name)?;
 
-    // Remove from run_on_startup if present
-    {
-        let mut store = state
-            .store
-            .lock()
-            .map_err(|e| eyre!("Failed to lock store: {}", e))?;
-        let key = format!("{}_run_on_startup", node_name);
-        if store.get(&key).is_some() {
-            store
-                .delete(&key)
-                .map_err(|e| eyre!("Failed to delete key: {}", e))?;
-            store
-                .save()
-                .map_err(|e| eyre!("Failed to save store: {}", e))?;
-        }
-    }
+    // Remove from node groups
+    remove_group_member(manager, &node_name)?;
 
-    // Remove from app state
+    // Remove from the node manager
     {
-        let mut app_state = state
-            .node_manager
+        let mut nodes = manager
+            .nodes
             .lock()
-            .map_err(|e| eyre!("Failed to lock app state: {}", e))?;
-        app_state.nodes.remove(&node_name);
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes.remove(&node_name);
     }
+    remove_node_metrics(manager, &node_name)?;
 
-    // Update tray menu
-    update_tray_menu(state)?;
+    manager
+        .events
+        .publish_node(NodeEvent::Deleted { node_name })?;
 
-    Ok(true)
+    Ok(())
 }
 
-pub fn open_admin_dashboard(app_handle: AppHandle, node_name: String) -> Result<bool> {
-    let config = get_node_ports(&node_name, &app_handle)?;
-    let url = format!("http://localhost:{}/admin-dashboard", config.server_port);
+pub fn open_admin_dashboard(manager: &NodeManager, node_name: String) -> Result<()> {
+    let config = get_node_ports(&node_name, manager)?;
+    let url = dashboard_url(config.server_port);
 
     let (cmd, args) = if cfg!(target_os = "windows") {
         ("cmd", vec!["/C", "start", url.as_str()])
@@ -538,52 +707,119 @@ pub fn open_admin_dashboard(app_handle: AppHandle, node_name: String) -> Result<
         .spawn()
         .map_err(|e| eyre!("Failed to open URL: {}", e))?;
 
-    Ok(true)
+    Ok(())
 }
 
-pub async fn start_nodes_on_startup(state: State<'_, AppState>) -> Result<()> {
-    let nodes_to_start = {
-        let store = state
-            .store
-            .lock()
-            .map_err(|e| eyre!("Failed to lock store: {}", e))?;
-        store
-            .keys()
-            .filter(|key| key.ends_with("_run_on_startup"))
-            .filter_map(|key| {
-                if let Some(Value::Bool(true)) = store.get(&key) {
-                    Some(key.trim_end_matches("_run_on_startup").to_string())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<String>>()
-    };
+pub async fn start_nodes_on_startup(manager: &NodeManager) -> Result<()> {
+    let settings = get_settings(manager)?;
+    let workspace = settings.workspace()?;
+    let nodes_to_start: Vec<String> = workspace
+        .nodes
+        .iter()
+        .filter(|(_, node)| node.run_on_startup)
+        .map(|(node_name, _)| node_name.clone())
+        .collect();
+
+    // Start nodes in group order so dependencies come up first. A node that fails to
+    // start or to become ready only keeps the nodes depending on it from starting
+    let mut failed: HashSet<String> = HashSet::new();
+    for (node_name, wait_until_ready) in order_nodes(&workspace.node_groups, &nodes_to_start) {
+        let failed_dependency = workspace
+            .node_groups
+            .iter()
+            .filter_map(|group| group.member(&node_name))
+            .flat_map(|member| &member.depends_on)
+            .find(|dependency| failed.contains(*dependency));
+        if let Some(dependency) = failed_dependency {
+            log::warn!(
+                node = node_name.as_str(), operation = "start_nodes_on_startup";
+                "Not starting node {} on startup, its dependency {} failed to start",
+                node_name, dependency
+            );
+            failed.insert(node_name);
+            continue;
+        }
 
-    for node_name in nodes_to_start {
-        let node_config = get_node_ports(&node_name, &state.app_handle)?;
-        if !is_port_in_use(node_config.server_port) && !is_port_in_use(node_config.swarm_port) {
-            start_node(state.clone(), node_name).await?;
+        if let Err(e) = start_node_on_startup(manager, &node_name, wait_until_ready).await {
+            log::error!(
+                node = node_name.as_str(), operation = "start_nodes_on_startup";
+                "Failed to start node {} on startup: {:?}", node_name, e
+            );
+            failed.insert(node_name);
         }
     }
     Ok(())
 }
 
-pub async fn stop_all_nodes(state: State<'_, AppState>) -> Result<()> {
+// Nodes whose ports are taken are assumed to be running already and are left alone
+async fn start_node_on_startup(
+    manager: &NodeManager,
+    node_name: &str,
+    wait_until_ready: bool,
+) -> Result<()> {
+    let node_config = get_node_ports(node_name, manager)?;
+    if is_port_in_use(node_config.server_port) || is_port_in_use(node_config.swarm_port) {
+        return Ok(());
+    }
+    start_node(manager, node_name.to_string()).await?;
+    if wait_until_ready {
+        wait_for_node_ready(manager, node_name).await?;
+    }
+    Ok(())
+}
+
+pub async fn stop_all_nodes(manager: &NodeManager) -> Result<()> {
     let node_names: Vec<String> = {
-        let manager = state
-            .node_manager
+        let nodes = manager
+            .nodes
             .lock()
             .map_err(|e| eyre!("Failed to lock store: {}", e))?;
-        manager.nodes.keys().cloned().collect()
+        nodes.keys().cloned().collect()
     };
 
-    for node_name in node_names {
-        match stop_node_process(state.clone(), node_name.clone()).await {
-            Ok(_) => println!("Successfully stopped node: {}", node_name),
-            Err(e) => eprintln!("Failed to stop node {}: {:?}", node_name, e),
+    // Stop nodes in reverse group order so dependents go down first
+    let groups = get_node_groups(manager)?;
+    for (node_name, _) in order_nodes(&groups, &node_names).into_iter().rev() {
+        if matches!(is_node_process_running(manager, &node_name), Ok(false)) {
+            continue;
+        }
+        match stop_node_process(manager, node_name.clone()).await {
+            Ok(_) => log::info!(
+                node = node_name.as_str(), operation = "stop_all_nodes";
+                "Successfully stopped node: {}", node_name
+            ),
+            Err(e) => log::error!(
+                node = node_name.as_str(), operation = "stop_all_nodes";
+                "Failed to stop node {}: {:?}", node_name, e
+            ),
         }
     }
 
     Ok(())
 }
+
+pub fn update_app_settings(manager: &NodeManager, settings: Settings) -> Result<()> {
+    let previous = get_settings(manager)?;
+    if previous.app.active_workspace != settings.app.active_workspace {
+        return Err(eyre!("Use switch_workspace to change the active workspace"));
+    }
+    let redactor = Redactor::new(&settings.app.redaction)?;
+    let settings_run_app_on_startup = settings.app.run_app_on_startup;
+    update_settings(manager, &settings)?;
+    set_app_log_level(settings.app.log_level);
+    *manager
+        .redactor
+        .lock()
+        .map_err(|e| eyre!("Failed to lock redactor: {}", e))? = redactor;
+
+    manager.events.publish(ManagerEvent::SettingsChanged)?;
+    if previous.app.run_app_on_startup != settings_run_app_on_startup {
+        manager
+            .events
+            .publish(ManagerEvent::RunAppOnStartupChanged {
+                enabled: settings_run_app_on_startup,
+            })?;
+    }
+
+    Ok(())
+}
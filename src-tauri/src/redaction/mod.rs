@@ -0,0 +1,107 @@
+// Masks secrets such as private keys, tokens and passwords in text written to the
+// node logs, published as node output or exported. Patterns with a `key` group keep
+// that part and mask the `value` group, the others mask the whole match.
+use crate::{manager::NodeManager, store::get_settings, types::RedactionSettings};
+use eyre::{eyre, Result};
+use regex::{Captures, Regex};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+const BUILTIN_PATTERNS: &[&str] = &[
+    // PEM private keys, up to the end of the text if the block is cut off
+    r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?(?:-----END [A-Z ]*PRIVATE KEY-----|$)",
+    // Assignments like `keypair = "..."`, `"password": "..."` or `auth_token=...`
+    r#"(?i)(?P<key>\b[\w.-]*(?:keypair|private[_-]?key|secret|password|passwd|token|api[_-]?key)[\w.-]*"?\s*[:=]\s*)(?P<value>"[^"]*"|'[^']*'|[^\s,;]+)"#,
+    r"(?i)(?P<key>\bbearer\s+)(?P<value>[\w.~+/-]+=*)",
+    // JSON web tokens
+    r"\beyJ[\w-]*\.[\w-]+\.[\w-]+",
+];
+
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            patterns: BUILTIN_PATTERNS
+                .iter()
+                .map(|pattern| Regex::new(pattern).unwrap())
+                .collect(),
+        }
+    }
+}
+
+impl Redactor {
+    // The built-in patterns followed by the custom ones, none if redaction is disabled
+    pub fn new(settings: &RedactionSettings) -> Result<Self> {
+        if !settings.enabled {
+            return Ok(Self {
+                patterns: Vec::new(),
+            });
+        }
+        let mut redactor = Self::default();
+        for pattern in &settings.patterns {
+            redactor.patterns.push(
+                Regex::new(pattern)
+                    .map_err(|e| eyre!("Invalid redaction pattern {}: {}", pattern, e))?,
+            );
+        }
+        Ok(redactor)
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for pattern in &self.patterns {
+            text = pattern.replace_all(&text, mask).to_string();
+        }
+        text
+    }
+}
+
+fn mask(caps: &Captures) -> String {
+    let (Some(key), Some(value)) = (caps.name("key"), caps.name("value")) else {
+        return REDACTED.to_string();
+    };
+    // Keep quoted values quoted, so configs stay parseable
+    if value.as_str().starts_with(['"', '\'']) {
+        format!("{}\"{}\"", key.as_str(), REDACTED)
+    } else {
+        format!("{}{}", key.as_str(), REDACTED)
+    }
+}
+
+// Rebuild the manager's redactor from the settings, falling back to the built-in
+// patterns if the stored custom ones are invalid
+pub fn refresh_redactor(manager: &NodeManager) -> Result<()> {
+    let settings = get_settings(manager)?.app.redaction;
+    let redactor = Redactor::new(&settings).unwrap_or_else(|e| {
+        log::warn!(operation = "refresh_redactor"; "{}", e);
+        Redactor::default()
+    });
+    *manager
+        .redactor
+        .lock()
+        .map_err(|e| eyre!("Failed to lock redactor: {}", e))? = redactor;
+    Ok(())
+}
+
+// Redact text written to the node logs or published as node output
+pub fn redact(manager: &NodeManager, text: &str) -> Result<String> {
+    let redactor = manager
+        .redactor
+        .lock()
+        .map_err(|e| eyre!("Failed to lock redactor: {}", e))?;
+    Ok(redactor.redact(text))
+}
+
+// Exported data is always redacted, with the custom patterns even if redaction of
+// the logs is disabled
+pub fn export_redactor(manager: &NodeManager) -> Result<Redactor> {
+    let settings = get_settings(manager)?.app.redaction;
+    Redactor::new(&RedactionSettings {
+        enabled: true,
+        ..settings
+    })
+}
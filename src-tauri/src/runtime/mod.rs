@@ -0,0 +1,57 @@
+use crate::error::ManagerError;
+use eyre::{bail, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// How meroctl is launched for the managed nodes. The desktop app and the CLI run the
+// bundled or configured binary as is, while tests swap in a stand-in binary and
+// script it through extra environment variables.
+#[derive(Debug, Clone)]
+pub struct NodeRuntime {
+    pub binary_path: PathBuf,
+    // Passed to every meroctl invocation, before the node's launch profile env
+    pub env: Vec<(String, String)>,
+}
+
+impl NodeRuntime {
+    pub fn new(binary_path: impl Into<PathBuf>) -> Self {
+        NodeRuntime {
+            binary_path: binary_path.into(),
+            env: Vec::new(),
+        }
+    }
+
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    // The binary, failing with BINARY_MISSING if it is not there
+    pub fn binary(&self) -> Result<&Path> {
+        if !self.binary_path.exists() {
+            bail!(ManagerError::BinaryMissing {
+                path: self.binary_path.display().to_string()
+            });
+        }
+        Ok(&self.binary_path)
+    }
+
+    // A meroctl command without arguments
+    pub fn command(&self) -> Result<Command> {
+        let mut command = Command::new(self.binary()?);
+        command.envs(self.env.iter().map(|(key, value)| (key, value)));
+        Ok(command)
+    }
+
+    // A meroctl command for a node in the given home directory, to which the
+    // subcommand and its arguments are appended
+    pub fn node_command(&self, node_name: &str, home: &Path) -> Result<Command> {
+        let mut command = self.command()?;
+        command
+            .arg("--node-name")
+            .arg(node_name)
+            .arg("--home")
+            .arg(home);
+        Ok(command)
+    }
+}
@@ -1,36 +1,466 @@
-use crate::types::AppState;
-use eyre::{eyre, Result};
-use tauri::State;
+use crate::{
+    backup::schedule::validate_backup_schedule,
+    limits::validate_resource_limits,
+    manager::NodeManager,
+    operations::groups::validate_group_structure,
+    types::{
+        default_workspace, AppSettings, LaunchProfile, NodeGroup, NodeSettings, ResourceLimits,
+        Settings, WorkspaceSettings,
+    },
+    utils::validate_launch_profile,
+};
+use eyre::{bail, eyre, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-pub fn update_run_node_on_startup(
-    state: &State<'_, AppState>,
-    node_name: &str,
-    run_on_startup: bool,
-) -> Result<()> {
-    let store_lock = state.store.lock();
-    if let Ok(mut store) = store_lock {
-        store
-            .insert(
-                format!("{}_run_on_startup", node_name),
-                serde_json::json!(run_on_startup),
-            )
-            .map_err(|e| eyre!("Failed to insert into store: {}", e))?;
-        store
-            .save()
-            .map_err(|e| eyre!("Failed to save store: {}", e))?;
+pub const SETTINGS_VERSION: u32 = 2;
+const SETTINGS_KEY: &str = "settings";
+
+// Suffixes of the per-node keys used before the settings schema was introduced
+const LEGACY_RUN_ON_STARTUP_SUFFIX: &str = "_run_on_startup";
+const LEGACY_RESOURCE_LIMITS_SUFFIX: &str = "_resource_limits";
+const LEGACY_LAUNCH_PROFILE_SUFFIX: &str = "_launch_profile";
+const LEGACY_APP_KEYS: [&str; 2] = ["run_app_on_startup", "node_groups"];
+
+// Key-value store persisted as a JSON object, in the same format as the store of
+// tauri-plugin-store used by earlier versions, so existing settings files keep working
+pub struct SettingsStore {
+    path: PathBuf,
+    // Home directory of the workspaces that have none of their own
+    default_home: PathBuf,
+    cache: HashMap<String, Value>,
+    // Parsed settings, dropped whenever the cached values change
+    settings: Option<Settings>,
+    // Modification time, size and inode of the file when it was last read or written
+    stamp: Option<(SystemTime, u64, u64)>,
+}
+
+// Exclusive advisory lock on the lock file next to the store, held from reading the
+// settings until they are written back so the app and the CLI sharing a data directory
+// don't overwrite each other's changes. Released when dropped
+pub struct StoreLock {
+    _file: fs::File,
+}
+
+// Every save renames a new file into place, so its inode tells writes apart that fall
+// within the resolution of the modification time
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    Some((metadata.modified().ok()?, metadata.len(), inode))
+}
+
+impl SettingsStore {
+    pub fn open(path: PathBuf, default_home: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            fs::write(&path, "{}").map_err(|e| eyre!("Failed to create store file: {}", e))?;
+        }
+        let mut store = SettingsStore {
+            path,
+            default_home,
+            cache: HashMap::new(),
+            settings: None,
+            stamp: None,
+        };
+        store.load()?;
+        Ok(store)
+    }
+
+    pub fn load(&mut self) -> Result<()> {
+        let stamp = file_stamp(&self.path);
+        let content =
+            fs::read(&self.path).map_err(|e| eyre!("Failed to read store file: {}", e))?;
+        self.cache =
+            serde_json::from_slice(&content).map_err(|e| eyre!("Failed to parse store: {}", e))?;
+        self.settings = None;
+        self.stamp = stamp;
+        Ok(())
+    }
+
+    // Re-read the file only if it was written by another manager process sharing the
+    // same data directory since it was last read or written here
+    pub fn reload_if_changed(&mut self) -> Result<()> {
+        if self.stamp.is_none() || file_stamp(&self.path) != self.stamp {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    pub fn lock(&self) -> Result<StoreLock> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))
+            .map_err(|e| eyre!("Failed to open store lock file: {}", e))?;
+        file.lock()
+            .map_err(|e| eyre!("Failed to lock store file: {}", e))?;
+        Ok(StoreLock { _file: file })
+    }
+
+    // Write to a temporary file first so the store is never left half written. Callers
+    // hold the lock from `lock` so that no other process writes in between
+    pub fn save(&mut self) -> Result<()> {
+        let content = serde_json::to_vec(&self.cache)
+            .map_err(|e| eyre!("Failed to serialize store: {}", e))?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, content).map_err(|e| eyre!("Failed to write store file: {}", e))?;
+        fs::rename(&tmp_path, &self.path).map_err(|e| eyre!("Failed to save store: {}", e))?;
+        self.stamp = file_stamp(&self.path);
         Ok(())
-    } else {
-        Err(eyre!("Failed to acquire store lock"))
     }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, value: Value) {
+        self.settings = None;
+        self.cache.insert(key, value);
+    }
+
+    pub fn delete(&mut self, key: &str) -> bool {
+        self.settings = None;
+        self.cache.remove(key).is_some()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.cache.keys()
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            app: AppSettings::default(),
+            workspaces: BTreeMap::from([(default_workspace(), WorkspaceSettings::default())]),
+        }
+    }
+}
+
+impl Settings {
+    // Settings of the active workspace, which scope the nodes and groups
+    pub fn workspace(&self) -> Result<&WorkspaceSettings> {
+        self.workspaces
+            .get(&self.app.active_workspace)
+            .ok_or_else(|| eyre!("Workspace not found: {}", self.app.active_workspace))
+    }
+
+    pub fn workspace_mut(&mut self) -> Result<&mut WorkspaceSettings> {
+        let active_workspace = &self.app.active_workspace;
+        self.workspaces
+            .get_mut(active_workspace)
+            .ok_or_else(|| eyre!("Workspace not found: {}", active_workspace))
+    }
+}
+
+// Bring the store up to the current settings schema, converting the flat keys
+// written by older versions on first load
+pub fn migrate_settings(store: &mut SettingsStore) -> Result<()> {
+    let _lock = store.lock()?;
+    store.load()?;
+    let settings = match store.get(SETTINGS_KEY) {
+        Some(value) => {
+            let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+            if version > SETTINGS_VERSION as u64 {
+                bail!(
+                    "Settings version {} is newer than the supported version {}",
+                    version,
+                    SETTINGS_VERSION
+                );
+            }
+            if version == SETTINGS_VERSION as u64 {
+                return Ok(());
+            }
+            migrate_v1(value.clone())?
+        }
+        None => migrate_legacy_keys(store)?,
+    };
+
+    write_settings(store, &settings)
+}
+
+// Version 1 kept the node settings and groups at the top level, version 2 moves them
+// into the default workspace
+fn migrate_v1(mut value: Value) -> Result<Settings> {
+    let mut workspace = serde_json::Map::new();
+    if let Some(nodes) = value.get_mut("nodes").map(Value::take) {
+        workspace.insert("nodes".to_string(), nodes);
+    }
+    if let Some(node_groups) = value
+        .get_mut("app")
+        .and_then(Value::as_object_mut)
+        .and_then(|app| app.remove("node_groups"))
+    {
+        workspace.insert("node_groups".to_string(), node_groups);
+    }
+
+    let mut settings: Settings =
+        serde_json::from_value(value).map_err(|e| eyre!("Failed to parse settings: {}", e))?;
+    let workspace: WorkspaceSettings = serde_json::from_value(Value::Object(workspace))
+        .map_err(|e| eyre!("Failed to migrate node settings: {}", e))?;
+    settings.version = SETTINGS_VERSION;
+    settings.app.active_workspace = default_workspace();
+    settings.workspaces.insert(default_workspace(), workspace);
+    Ok(settings)
+}
+
+fn migrate_legacy_keys(store: &mut SettingsStore) -> Result<Settings> {
+    let mut settings = Settings::default();
+    let keys: Vec<String> = store.keys().cloned().collect();
+
+    if let Some(value) = store.get("run_app_on_startup").and_then(Value::as_bool) {
+        settings.app.run_app_on_startup = value;
+    }
+    let workspace = settings.workspace_mut()?;
+    if let Some(value) = store.get("node_groups") {
+        workspace.node_groups = serde_json::from_value(value.clone())
+            .map_err(|e| eyre!("Failed to migrate node groups: {}", e))?;
+    }
+
+    for key in &keys {
+        if LEGACY_APP_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let Some(value) = store.get(key).cloned() else {
+            continue;
+        };
+
+        if let Some(node_name) = key.strip_suffix(LEGACY_RUN_ON_STARTUP_SUFFIX) {
+            let node = workspace.nodes.entry(node_name.to_string()).or_default();
+            node.run_on_startup = value.as_bool().unwrap_or(false);
+        } else if let Some(node_name) = key.strip_suffix(LEGACY_RESOURCE_LIMITS_SUFFIX) {
+            let node = workspace.nodes.entry(node_name.to_string()).or_default();
+            node.resource_limits = serde_json::from_value(value)
+                .map_err(|e| eyre!("Failed to migrate resource limits of {}: {}", node_name, e))?;
+        } else if let Some(node_name) = key.strip_suffix(LEGACY_LAUNCH_PROFILE_SUFFIX) {
+            let node = workspace.nodes.entry(node_name.to_string()).or_default();
+            node.launch_profile = serde_json::from_value(value)
+                .map_err(|e| eyre!("Failed to migrate launch profile of {}: {}", node_name, e))?;
+        } else {
+            continue;
+        }
+
+        store.delete(key);
+    }
+
+    for key in LEGACY_APP_KEYS {
+        store.delete(key);
+    }
+
+    Ok(settings)
+}
+
+pub fn validate_settings(settings: &Settings, default_home: &Path) -> Result<()> {
+    if settings.version != SETTINGS_VERSION {
+        bail!(
+            "Unsupported settings version {}, expected {}",
+            settings.version,
+            SETTINGS_VERSION
+        );
+    }
+
+    if !settings
+        .workspaces
+        .contains_key(&settings.app.active_workspace)
+    {
+        bail!(
+            "Active workspace {} does not exist",
+            settings.app.active_workspace
+        );
+    }
+
+    let mut homes = HashSet::new();
+    for (workspace_name, workspace) in &settings.workspaces {
+        if workspace_name.trim().is_empty() {
+            bail!("Workspace name cannot be empty");
+        }
+        let home = workspace.home.as_deref().unwrap_or(default_home);
+        if !homes.insert(home) {
+            bail!(
+                "Workspace {} uses the same home directory as another workspace",
+                workspace_name
+            );
+        }
+        validate_workspace(workspace)
+            .map_err(|e| eyre!("Invalid workspace {}: {}", workspace_name, e))?;
+    }
+
+    Ok(())
+}
+
+fn validate_workspace(workspace: &WorkspaceSettings) -> Result<()> {
+    let mut group_names = HashSet::new();
+    for group in &workspace.node_groups {
+        if !group_names.insert(group.name.as_str()) {
+            bail!("Group {} is defined more than once", group.name);
+        }
+        validate_group_structure(group)?;
+    }
+
+    for (node_name, node) in &workspace.nodes {
+        if node_name.trim().is_empty() {
+            bail!("Node name cannot be empty");
+        }
+        validate_resource_limits(&node.resource_limits)
+            .map_err(|e| eyre!("Invalid resource limits for node {}: {}", node_name, e))?;
+        validate_launch_profile(&node.launch_profile)
+            .map_err(|e| eyre!("Invalid launch profile for node {}: {}", node_name, e))?;
+        if let Some(schedule) = &node.backup_schedule {
+            validate_backup_schedule(schedule)
+                .map_err(|e| eyre!("Invalid backup schedule for node {}: {}", node_name, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Settings parsed from the store, which are kept until the store changes
+pub fn read_settings(store: &mut SettingsStore) -> Result<Settings> {
+    if let Some(settings) = &store.settings {
+        return Ok(settings.clone());
+    }
+    let settings = match store.get(SETTINGS_KEY) {
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| eyre!("Failed to parse settings: {}", e))?,
+        None => Settings::default(),
+    };
+    store.settings = Some(settings.clone());
+    Ok(settings)
 }
 
-pub fn get_run_node_on_startup(state: &State<'_, AppState>, node_name: &str) -> Result<bool> {
-    let store = state
+pub fn write_settings(store: &mut SettingsStore, settings: &Settings) -> Result<()> {
+    validate_settings(settings, &store.default_home)?;
+    store.insert(SETTINGS_KEY.to_string(), serde_json::json!(settings));
+    store.save()?;
+    store.settings = Some(settings.clone());
+    Ok(())
+}
+
+pub fn get_settings(manager: &NodeManager) -> Result<Settings> {
+    let mut store = manager
+        .store
+        .lock()
+        .map_err(|e| eyre!("Failed to acquire store lock: {}", e))?;
+    store.reload_if_changed()?;
+    read_settings(&mut store)
+}
+
+pub fn update_settings(manager: &NodeManager, settings: &Settings) -> Result<()> {
+    let mut store = manager
         .store
         .lock()
         .map_err(|e| eyre!("Failed to acquire store lock: {}", e))?;
-    Ok(store
-        .get(&format!("{}_run_on_startup", node_name))
-        .and_then(|value| value.as_bool())
-        .unwrap_or(false))
+    let _lock = store.lock()?;
+    write_settings(&mut store, settings)
+}
+
+// Read, modify and write back the settings while holding the store lock
+pub fn modify_settings<F>(manager: &NodeManager, modify: F) -> Result<()>
+where
+    F: FnOnce(&mut Settings) -> Result<()>,
+{
+    let mut store = manager
+        .store
+        .lock()
+        .map_err(|e| eyre!("Failed to acquire store lock: {}", e))?;
+    // The modification time may not have changed after a quick write by another
+    // process, so the file is always read again once it is locked
+    let _lock = store.lock()?;
+    store.load()?;
+    let mut settings = read_settings(&mut store)?;
+    modify(&mut settings)?;
+    write_settings(&mut store, &settings)
+}
+
+pub fn get_node_settings(manager: &NodeManager, node_name: &str) -> Result<NodeSettings> {
+    Ok(get_settings(manager)?
+        .workspace()?
+        .nodes
+        .get(node_name)
+        .cloned()
+        .unwrap_or_default())
+}
+
+pub fn rename_node_settings(
+    manager: &NodeManager,
+    original_node_name: &str,
+    node_name: &str,
+) -> Result<()> {
+    modify_settings(manager, |settings| {
+        let workspace = settings.workspace_mut()?;
+        if let Some(node) = workspace.nodes.remove(original_node_name) {
+            workspace.nodes.insert(node_name.to_string(), node);
+        }
+        Ok(())
+    })
+}
+
+pub fn remove_node_settings(manager: &NodeManager, node_name: &str) -> Result<()> {
+    modify_settings(manager, |settings| {
+        settings.workspace_mut()?.nodes.remove(node_name);
+        Ok(())
+    })
+}
+
+pub fn update_run_node_on_startup(
+    manager: &NodeManager,
+    node_name: &str,
+    run_on_startup: bool,
+) -> Result<()> {
+    modify_settings(manager, |settings| {
+        settings
+            .workspace_mut()?
+            .nodes
+            .entry(node_name.to_string())
+            .or_default()
+            .run_on_startup = run_on_startup;
+        Ok(())
+    })
+}
+
+pub fn get_run_node_on_startup(manager: &NodeManager, node_name: &str) -> Result<bool> {
+    Ok(get_node_settings(manager, node_name)?.run_on_startup)
+}
+
+pub fn get_node_groups(manager: &NodeManager) -> Result<Vec<NodeGroup>> {
+    Ok(get_settings(manager)?.workspace()?.node_groups.clone())
+}
+
+pub fn save_node_groups(manager: &NodeManager, groups: &[NodeGroup]) -> Result<()> {
+    modify_settings(manager, |settings| {
+        settings.workspace_mut()?.node_groups = groups.to_vec();
+        Ok(())
+    })
+}
+
+pub fn get_node_resource_limits(manager: &NodeManager, node_name: &str) -> Result<ResourceLimits> {
+    Ok(get_node_settings(manager, node_name)?.resource_limits)
+}
+
+pub fn get_node_launch_profile(manager: &NodeManager, node_name: &str) -> Result<LaunchProfile> {
+    Ok(get_node_settings(manager, node_name)?.launch_profile)
+}
+
+pub fn update_node_launch_profile(
+    manager: &NodeManager,
+    node_name: &str,
+    profile: &LaunchProfile,
+) -> Result<()> {
+    modify_settings(manager, |settings| {
+        settings
+            .workspace_mut()?
+            .nodes
+            .entry(node_name.to_string())
+            .or_default()
+            .launch_profile = profile.clone();
+        Ok(())
+    })
 }
@@ -0,0 +1,222 @@
+use crate::{
+    events::ManagerEvent,
+    manager::NodeManager,
+    types::{ContextEvent, NodeSubscription, SubscriptionStatus},
+    utils::get_node_ports,
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+// Keep the last 500 events per node
+const MAX_EVENTS: usize = 500;
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// Subscribe to the events of the given contexts on a node, replacing its previous
+// subscription. The node does not have to be running, the relay connects once it
+// is and reconnects whenever the connection drops. Must be called on the async
+// runtime the relay runs on.
+pub fn subscribe_to_contexts(
+    manager: &NodeManager,
+    node_name: String,
+    context_ids: Vec<String>,
+) -> Result<SubscriptionStatus> {
+    get_node_ports(&node_name, manager)?;
+    if context_ids.is_empty() {
+        bail!("Select at least one context to subscribe to");
+    }
+
+    let status = SubscriptionStatus {
+        node_name: node_name.clone(),
+        context_ids,
+        connected: false,
+        last_error: None,
+    };
+    let shared_status = Arc::new(Mutex::new(status.clone()));
+    let task = tokio::spawn(run_relay(
+        manager.clone(),
+        status.clone(),
+        Arc::clone(&shared_status),
+    ));
+
+    manager
+        .subscriptions
+        .lock()
+        .map_err(|e| eyre!("Failed to lock subscriptions: {}", e))?
+        .insert(
+            node_name,
+            NodeSubscription {
+                status: shared_status,
+                task,
+            },
+        );
+    Ok(status)
+}
+
+pub fn unsubscribe_from_contexts(manager: &NodeManager, node_name: &str) -> Result<()> {
+    manager
+        .subscriptions
+        .lock()
+        .map_err(|e| eyre!("Failed to lock subscriptions: {}", e))?
+        .remove(node_name);
+    Ok(())
+}
+
+pub fn list_subscriptions(manager: &NodeManager) -> Result<Vec<SubscriptionStatus>> {
+    let subscriptions = manager
+        .subscriptions
+        .lock()
+        .map_err(|e| eyre!("Failed to lock subscriptions: {}", e))?;
+    let mut statuses = subscriptions
+        .values()
+        .map(|subscription| {
+            subscription
+                .status
+                .lock()
+                .map(|status| status.clone())
+                .map_err(|e| eyre!("Failed to lock subscription status: {}", e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    statuses.sort_by(|a, b| a.node_name.cmp(&b.node_name));
+    Ok(statuses)
+}
+
+// Events received from the node, oldest first, optionally only those of one context
+pub fn get_context_events(
+    manager: &NodeManager,
+    node_name: &str,
+    context_id: Option<&str>,
+) -> Result<Vec<ContextEvent>> {
+    let events = manager
+        .context_events
+        .lock()
+        .map_err(|e| eyre!("Failed to lock context events: {}", e))?;
+    Ok(events
+        .get(node_name)
+        .map(|events| {
+            events
+                .iter()
+                .filter(|event| context_id.is_none() || event.context_id.as_deref() == context_id)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Keep the relay connected until the subscription is dropped, backing off between
+// failed connection attempts
+async fn run_relay(
+    manager: NodeManager,
+    subscription: SubscriptionStatus,
+    status: Arc<Mutex<SubscriptionStatus>>,
+) {
+    let mut delay = MIN_RECONNECT_DELAY;
+    loop {
+        let error = match relay_events(&manager, &subscription, &status).await {
+            Ok(()) => "Connection closed by the node".to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        let was_connected = match status.lock() {
+            Ok(mut status) => {
+                let was_connected = status.connected;
+                status.connected = false;
+                status.last_error = Some(error);
+                was_connected
+            }
+            Err(_) => false,
+        };
+        if was_connected {
+            delay = MIN_RECONNECT_DELAY;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+async fn relay_events(
+    manager: &NodeManager,
+    subscription: &SubscriptionStatus,
+    status: &Mutex<SubscriptionStatus>,
+) -> Result<()> {
+    // The ports are read on every attempt as the node config may have changed
+    let config = get_node_ports(&subscription.node_name, manager)?;
+    let url = format!("ws://127.0.0.1:{}/ws", config.server_port);
+    let (mut socket, _) = connect_async(&url)
+        .await
+        .map_err(|e| eyre!("Failed to connect to {}: {}", url, e))?;
+
+    let request = json!({
+        "id": 1,
+        "method": "subscribe",
+        "params": { "contextIds": subscription.context_ids },
+    });
+    socket
+        .send(Message::Text(request.to_string()))
+        .await
+        .map_err(|e| eyre!("Failed to send subscribe request: {}", e))?;
+
+    if let Ok(mut status) = status.lock() {
+        status.connected = true;
+        status.last_error = None;
+    }
+
+    while let Some(message) = socket.next().await {
+        match message.map_err(|e| eyre!("WebSocket connection failed: {}", e))? {
+            Message::Text(text) => handle_message(manager, &subscription.node_name, &text)?,
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn handle_message(manager: &NodeManager, node_name: &str, text: &str) -> Result<()> {
+    // Messages that are not JSON are not events, skip them
+    let Ok(message) = serde_json::from_str::<Value>(text) else {
+        return Ok(());
+    };
+
+    // Responses to the subscribe request carry its id, events have none
+    if !message["id"].is_null() {
+        if let Some(error) = message.get("error") {
+            bail!("Failed to subscribe: {}", error);
+        }
+        return Ok(());
+    }
+
+    let result = message.get("result").cloned().unwrap_or(Value::Null);
+    let event = ContextEvent {
+        timestamp: Local::now().timestamp_millis(),
+        context_id: result["contextId"].as_str().map(String::from),
+        event_type: result["type"].as_str().map(String::from),
+        data: result.get("data").cloned().unwrap_or(result),
+    };
+    push_event(manager, node_name, event.clone())?;
+    manager.events.publish(ManagerEvent::ContextEvent {
+        node_name: node_name.to_string(),
+        event,
+    })
+}
+
+fn push_event(manager: &NodeManager, node_name: &str, event: ContextEvent) -> Result<()> {
+    let mut events = manager
+        .context_events
+        .lock()
+        .map_err(|e| eyre!("Failed to lock context events: {}", e))?;
+    let events = events
+        .entry(node_name.to_string())
+        .or_insert_with(VecDeque::new);
+    if events.len() == MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(event);
+    Ok(())
+}
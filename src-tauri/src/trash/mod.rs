@@ -0,0 +1,211 @@
+use crate::{
+    error::ManagerError,
+    logger::{create_log_file, get_log_file_path, node_log_path},
+    manager::NodeManager,
+    store::{get_node_settings, get_settings, modify_settings},
+    types::{NodeEvent, NodeProcess, TrashedNode},
+    utils::{
+        get_nodes_dir, get_trash_dir, get_workspace_nodes_dir, is_node_process_running, move_path,
+        validate_node_name,
+    },
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const TRASH_INFO_FILE: &str = "trash.json";
+// Directory inside a trash entry holding the node home directory
+const NODE_DIR: &str = "node";
+// Log of a node imported as a link, which is kept outside of its directory
+const LINKED_LOG_FILE: &str = "node.log";
+
+// Move the node directory into the trash together with its settings, so it can be
+// restored later
+pub fn move_to_trash(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let node_dir = get_nodes_dir(manager).join(node_name);
+    if !node_dir.exists() {
+        bail!(ManagerError::NodeNotFound {
+            node_name: node_name.to_string()
+        });
+    }
+    if is_node_process_running(manager, node_name)? {
+        bail!(ManagerError::NodeRunning {
+            node_name: node_name.to_string()
+        });
+    }
+
+    let deleted_at = Local::now();
+    let id = format!("{}-{}", node_name, deleted_at.format("%Y%m%dT%H%M%S%.3f"));
+    let entry_dir = get_trash_dir(manager).join(&id);
+    fs::create_dir_all(&entry_dir).map_err(|e| eyre!("Failed to create trash directory: {}", e))?;
+
+    let trashed_node = TrashedNode {
+        id,
+        node_name: node_name.to_string(),
+        workspace: get_settings(manager)?.app.active_workspace,
+        deleted_at: deleted_at.timestamp(),
+        settings: get_node_settings(manager, node_name)?,
+    };
+    let info = serde_json::to_string_pretty(&trashed_node)
+        .map_err(|e| eyre!("Failed to serialize trash entry: {}", e))?;
+    fs::write(entry_dir.join(TRASH_INFO_FILE), info)
+        .map_err(|e| eyre!("Failed to write trash entry: {}", e))?;
+
+    let linked_log = node_dir
+        .is_symlink()
+        .then(|| get_log_file_path(manager, node_name));
+    if let Err(e) = move_path(&node_dir, &entry_dir.join(NODE_DIR)) {
+        let _ = fs::remove_dir_all(&entry_dir);
+        bail!("Failed to move node directory to trash: {}", e);
+    }
+    if let Some(linked_log) = linked_log {
+        let _ = move_path(&linked_log, &entry_dir.join(LINKED_LOG_FILE));
+    }
+
+    Ok(())
+}
+
+fn read_trash_entry(entry_dir: &Path) -> Result<TrashedNode> {
+    let info = fs::read_to_string(entry_dir.join(TRASH_INFO_FILE))
+        .map_err(|e| eyre!("Failed to read trash entry: {}", e))?;
+    serde_json::from_str(&info).map_err(|e| eyre!("Failed to parse trash entry: {}", e))
+}
+
+// Trashed nodes, most recently deleted first
+pub fn list_trash(manager: &NodeManager) -> Result<Vec<TrashedNode>> {
+    let trash_dir = get_trash_dir(manager);
+    if !trash_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut trashed_nodes = Vec::new();
+    for entry in
+        fs::read_dir(&trash_dir).map_err(|e| eyre!("Failed to read trash directory: {}", e))?
+    {
+        let entry = entry.map_err(|e| eyre!("Failed to read directory entry: {}", e))?;
+        match read_trash_entry(&entry.path()) {
+            Ok(trashed_node) => trashed_nodes.push(trashed_node),
+            Err(e) => log::warn!(
+                operation = "list_trash";
+                "Skipping invalid trash entry {}: {}",
+                entry.path().display(),
+                e
+            ),
+        }
+    }
+    trashed_nodes.sort_by_key(|node| std::cmp::Reverse(node.deleted_at));
+    Ok(trashed_nodes)
+}
+
+pub fn restore_from_trash(
+    manager: &NodeManager,
+    id: String,
+    node_name: Option<String>,
+) -> Result<String> {
+    let entry_dir = get_trash_dir(manager).join(&id);
+    if id.contains(['/', '\\']) || !entry_dir.exists() {
+        bail!("Trash entry not found: {}", id);
+    }
+    let trashed_node = read_trash_entry(&entry_dir)?;
+
+    let node_name = node_name.unwrap_or(trashed_node.node_name);
+    validate_node_name(&node_name)?;
+    // The node goes back into the workspace it was deleted from, which may not be the
+    // active one
+    let settings = get_settings(manager)?;
+    let workspace = settings
+        .workspaces
+        .get(&trashed_node.workspace)
+        .ok_or_else(|| {
+            eyre!(
+                "Workspace {} of the trashed node no longer exists",
+                trashed_node.workspace
+            )
+        })?;
+    let is_active_workspace = trashed_node.workspace == settings.app.active_workspace;
+    let nodes_dir = get_workspace_nodes_dir(manager, workspace);
+    let node_dir = nodes_dir.join(&node_name);
+    if node_dir.exists() {
+        bail!(ManagerError::NodeAlreadyExists { node_name });
+    }
+
+    fs::create_dir_all(&nodes_dir).map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
+    move_path(&entry_dir.join(NODE_DIR), &node_dir)
+        .map_err(|e| eyre!("Failed to restore node directory: {}", e))?;
+    if node_dir.is_symlink() {
+        let _ = move_path(
+            &entry_dir.join(LINKED_LOG_FILE),
+            &node_log_path(&nodes_dir, &node_name),
+        );
+    }
+    fs::remove_dir_all(&entry_dir).map_err(|e| eyre!("Failed to remove trash entry: {}", e))?;
+
+    modify_settings(manager, |settings| {
+        settings
+            .workspaces
+            .get_mut(&trashed_node.workspace)
+            .ok_or_else(|| eyre!("Workspace not found: {}", trashed_node.workspace))?
+            .nodes
+            .insert(node_name.clone(), trashed_node.settings.clone());
+        Ok(())
+    })?;
+
+    // Nodes of other workspaces are loaded when their workspace is activated
+    if !is_active_workspace {
+        return Ok(node_name);
+    }
+
+    let log_file = create_log_file(manager, &node_name)
+        .map_err(|e| eyre!("Failed to open log file: {}", e))?;
+    {
+        let mut nodes = manager
+            .nodes
+            .lock()
+            .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+        nodes.insert(
+            node_name.clone(),
+            NodeProcess {
+                process: None,
+                stdin: None,
+                output: Arc::new(Mutex::new(String::new())),
+                log_file: Some(log_file),
+            },
+        );
+    }
+
+    manager.events.publish_node(NodeEvent::Created {
+        node_name: node_name.clone(),
+    })?;
+
+    Ok(node_name)
+}
+
+// Permanently delete trashed nodes older than the retention period
+pub fn purge_expired_trash(manager: &NodeManager, retention_days: u32) -> Result<Vec<String>> {
+    let cutoff = Local::now().timestamp() - i64::from(retention_days) * 24 * 60 * 60;
+    let mut purged = Vec::new();
+    for trashed_node in list_trash(manager)? {
+        if trashed_node.deleted_at > cutoff {
+            continue;
+        }
+        fs::remove_dir_all(get_trash_dir(manager).join(&trashed_node.id))
+            .map_err(|e| eyre!("Failed to purge trash entry {}: {}", trashed_node.id, e))?;
+        purged.push(trashed_node.id);
+    }
+    Ok(purged)
+}
+
+pub fn start_trash_purger(manager: NodeManager) {
+    std::thread::spawn(move || loop {
+        let result = get_settings(&manager)
+            .and_then(|settings| purge_expired_trash(&manager, settings.app.trash_retention_days));
+        if let Err(e) = result {
+            log::error!(operation = "purge_trash"; "Error purging trash: {:?}", e);
+        }
+        std::thread::sleep(PURGE_INTERVAL);
+    });
+}
@@ -1,10 +1,8 @@
-use eyre::eyre;
 use tauri::{CustomMenuItem, Manager, Menu, MenuItem, Submenu, WindowMenuEvent};
 
 use crate::{
-    operations::stop_all_nodes,
-    types::AppState,
-    utils::setup::{disable_auto_launch, setup_auto_launch},
+    manager::NodeManager, operations::stop_all_nodes, store::modify_settings,
+    utils::setup::apply_run_app_on_startup,
 };
 
 pub fn create_menu() -> Menu {
@@ -44,44 +42,24 @@ pub fn handle_menu_click(event: &WindowMenuEvent) -> Result<(), eyre::Report> {
 
     match event.menu_item_id() {
         "run_on_startup" => {
-            let app_state = app_handle.state::<AppState>();
-            let mut store = app_state.store.lock().unwrap();
+            let manager = app_handle.state::<NodeManager>();
 
-            // Toggle the run_app_on_startup value
-            let current_value = store
-                .get("run_app_on_startup")
-                .and_then(|value| value.as_bool())
-                .unwrap_or(false);
-            let new_value = !current_value;
-            if new_value {
-                setup_auto_launch(&app_handle)
-                    .map_err(|e| eyre!("Failed to setup auto launch: {}", e))?;
-            } else {
-                disable_auto_launch(&app_handle)
-                    .map_err(|e| eyre!("Failed to disable auto launch: {}", e))?;
-            }
-            // Update the store
-            store
-                .insert("run_app_on_startup".to_string(), new_value.into())
-                .map_err(|e| eyre!("Failed to update store: {}", e))?;
-            store
-                .save()
-                .map_err(|e| eyre!("Failed to save store: {}", e))?;
+            // Toggle the run_app_on_startup setting
+            let mut new_value = false;
+            modify_settings(&manager, |settings| {
+                settings.app.run_app_on_startup = !settings.app.run_app_on_startup;
+                new_value = settings.app.run_app_on_startup;
+                Ok(())
+            })?;
 
-            // Update the menu item state
-            event
-                .window()
-                .menu_handle()
-                .get_item("run_on_startup")
-                .set_selected(new_value)
-                .map_err(|e| eyre!("Failed to update menu item state: {}", e))?;
+            apply_run_app_on_startup(&app_handle, new_value)?;
         }
         "quit" => {
             // Stop all nodes and exit the application
             let app_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = stop_all_nodes(app_handle.state::<AppState>()).await {
-                    eprintln!("Failed to stop all nodes: {}", e);
+                if let Err(e) = stop_all_nodes(&app_handle.state::<NodeManager>()).await {
+                    log::error!(operation = "stop_all_nodes"; "Failed to stop all nodes: {}", e);
                 }
                 app_handle.exit(0);
             });
@@ -1,21 +1,31 @@
+use crate::events::ManagerEvent;
+use crate::manager::NodeManager;
+use crate::metrics::{format_metrics_summary, latest_metrics_summaries};
+use crate::operations::get_nodes;
+use crate::operations::groups::{restart_group, start_group, stop_group};
 use crate::operations::{open_admin_dashboard, stop_all_nodes};
-use crate::types::NodeInfo;
-use crate::{operations::get_nodes, types::AppState, utils::is_node_process_running};
+use crate::store::get_node_groups;
+use crate::types::{HealthStatus, NodeGroup, NodeInfo, WorkspaceInfo};
+use crate::utils::get_node_peer_info;
+use crate::workspace::{list_workspaces, switch_workspace};
 use eyre::eyre;
+use std::collections::HashMap;
 use tauri::{
-    AppHandle, CustomMenuItem, Manager, State, SystemTrayMenu, SystemTrayMenuItem,
+    AppHandle, ClipboardManager, CustomMenuItem, Manager, SystemTrayMenu, SystemTrayMenuItem,
     SystemTraySubmenu,
 };
 
 pub mod menu;
 
-pub fn update_tray_menu(state: State<'_, AppState>) -> Result<(), eyre::Report> {
-    let app_handle = state.app_handle.clone();
-    let nodes = get_nodes(state)?;
+pub fn update_tray_menu(app_handle: &AppHandle, manager: &NodeManager) -> Result<(), eyre::Report> {
+    let groups = get_node_groups(manager)?;
+    let summaries = latest_metrics_summaries(manager)?;
+    let workspaces = list_workspaces(manager)?;
+    let nodes = get_nodes(manager)?;
     let menu = match nodes.len() {
-        0 => build_empty_node_menu()?,
-        1 => build_single_node_menu(&app_handle, &nodes[0].name)?,
-        _ => build_multi_node_menu(&app_handle, &nodes)?,
+        0 => build_empty_node_menu(&workspaces)?,
+        1 => build_single_node_menu(&nodes[0], &groups, &summaries, &workspaces)?,
+        _ => build_multi_node_menu(&nodes, &groups, &summaries, &workspaces)?,
     };
 
     app_handle.tray_handle().set_menu(menu)?;
@@ -23,57 +33,71 @@ pub fn update_tray_menu(state: State<'_, AppState>) -> Result<(), eyre::Report>
 }
 
 fn build_single_node_menu(
-    app_handle: &AppHandle,
-    node: &str,
+    node: &NodeInfo,
+    groups: &[NodeGroup],
+    summaries: &HashMap<String, String>,
+    workspaces: &[WorkspaceInfo],
 ) -> Result<SystemTrayMenu, eyre::Report> {
-    // Initialize status_icon and is_running based on the node's running status
-    let (status_icon, is_running) = match is_node_process_running(app_handle, node) {
-        Ok(true) => ("🟢", true),
-        Ok(false) => ("🔴", false),
-        Err(_) => ("⚠️", true),
-    };
     let mut menu = SystemTrayMenu::new();
     menu = menu
         .add_item(CustomMenuItem::new(
-            format!("show_{}", node),
-            format!("{} {}", status_icon, node),
+            format!("show_{}", node.name),
+            format!("{} {}", status_icon(node), node.name),
         ))
         .add_native_item(SystemTrayMenuItem::Separator);
 
-    menu = add_node_items(menu, node, is_running)?;
+    menu = add_metrics_item(menu, node, summaries);
+    menu = add_node_items(menu, node)?;
+    menu = add_group_submenus(menu, groups);
+    menu = add_workspace_submenu(menu, workspaces);
 
     Ok(menu
         .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(CustomMenuItem::new("quit".to_string(), "Quit")))
 }
 
-fn build_empty_node_menu() -> Result<SystemTrayMenu, eyre::Report> {
+// Status icon shown next to the node name, based on the latest health probe
+fn status_icon(node: &NodeInfo) -> &'static str {
+    if node.external_node {
+        return "⚠️";
+    }
+    match node.health {
+        HealthStatus::Healthy => "🟢",
+        HealthStatus::Starting => "🟡",
+        HealthStatus::Degraded => "🟠",
+        HealthStatus::Unreachable => "🔴",
+        HealthStatus::Stopped => "⚪",
+    }
+}
+
+fn build_empty_node_menu(workspaces: &[WorkspaceInfo]) -> Result<SystemTrayMenu, eyre::Report> {
     let no_nodes_item = CustomMenuItem::new("show_window".to_string(), "No nodes available");
-    Ok(SystemTrayMenu::new()
-        .add_item(no_nodes_item)
+    let menu = add_workspace_submenu(SystemTrayMenu::new().add_item(no_nodes_item), workspaces);
+    Ok(menu
         .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(CustomMenuItem::new("quit".to_string(), "Quit")))
 }
 
 fn build_multi_node_menu(
-    app_handle: &AppHandle,
     nodes: &Vec<NodeInfo>,
+    groups: &[NodeGroup],
+    summaries: &HashMap<String, String>,
+    workspaces: &[WorkspaceInfo],
 ) -> Result<SystemTrayMenu, eyre::Report> {
     let mut menu = SystemTrayMenu::new();
 
     for node in nodes {
-        // Initialize status_icon and is_running based on the node's running status
-        let (status_icon, is_running) = match is_node_process_running(app_handle, &node.name) {
-            Ok(true) => ("🟢", true),
-            Ok(false) => ("🔴", false),
-            Err(_) => ("⚠️", true),
-        };
-        let node_menu = add_node_items(SystemTrayMenu::new(), &node.name, is_running)?;
+        let node_menu = add_node_items(
+            add_metrics_item(SystemTrayMenu::new(), node, summaries),
+            node,
+        )?;
         menu = menu.add_submenu(SystemTraySubmenu::new(
-            format!("{} {}", status_icon, node.name),
+            format!("{} {}", status_icon(node), node.name),
             node_menu,
         ));
     }
+    menu = add_group_submenus(menu, groups);
+    menu = add_workspace_submenu(menu, workspaces);
 
     Ok(menu
         .add_native_item(SystemTrayMenuItem::Separator)
@@ -82,9 +106,10 @@ fn build_multi_node_menu(
 
 fn add_node_items(
     menu: SystemTrayMenu,
-    node: &str,
-    is_running: bool,
+    node_info: &NodeInfo,
 ) -> Result<SystemTrayMenu, eyre::Report> {
+    let node = node_info.name.as_str();
+    let is_running = node_info.is_running || node_info.external_node;
     Ok(menu
         .add_item(CustomMenuItem::new(
             format!("show_{}", node),
@@ -114,22 +139,146 @@ fn add_node_items(
                 item.disabled()
             }
         })
+        .add_item({
+            let item = CustomMenuItem::new(format!("copypeer_{}", node), "Copy peer ID");
+            if node_info.peer_info.peer_id.is_some() {
+                item
+            } else {
+                item.disabled()
+            }
+        })
+        .add_item({
+            let item = CustomMenuItem::new(format!("copyaddr_{}", node), "Copy multiaddr");
+            if node_info.peer_info.multiaddrs.is_empty() {
+                item.disabled()
+            } else {
+                item
+            }
+        })
         .add_item(CustomMenuItem::new(format!("config_{}", node), "Configure"))
         .add_item(CustomMenuItem::new(format!("logs_{}", node), "Logs"))
         .add_item(CustomMenuItem::new(format!("delete_{}", node), "Delete")))
 }
 
+// Resource usage summary for nodes run by the application, updated by the metrics collector
+fn add_metrics_item(
+    menu: SystemTrayMenu,
+    node: &NodeInfo,
+    summaries: &HashMap<String, String>,
+) -> SystemTrayMenu {
+    if !node.is_running {
+        return menu;
+    }
+    let summary = summaries
+        .get(&node.name)
+        .cloned()
+        .unwrap_or_else(|| "Collecting metrics...".to_string());
+    menu.add_item(CustomMenuItem::new(format!("metrics_{}", node.name), summary).disabled())
+}
+
+fn add_group_submenus(mut menu: SystemTrayMenu, groups: &[NodeGroup]) -> SystemTrayMenu {
+    if groups.is_empty() {
+        return menu;
+    }
+
+    menu = menu.add_native_item(SystemTrayMenuItem::Separator);
+    for group in groups {
+        let group_menu = SystemTrayMenu::new()
+            .add_item(CustomMenuItem::new(
+                format!("groupstart_{}", group.name),
+                "Start group",
+            ))
+            .add_item(CustomMenuItem::new(
+                format!("groupstop_{}", group.name),
+                "Stop group",
+            ))
+            .add_item(CustomMenuItem::new(
+                format!("grouprestart_{}", group.name),
+                "Restart group",
+            ));
+        menu = menu.add_submenu(SystemTraySubmenu::new(
+            format!("Group: {}", group.name),
+            group_menu,
+        ));
+    }
+    menu
+}
+
+// Switch between workspaces, only shown when more than one is configured
+fn add_workspace_submenu(menu: SystemTrayMenu, workspaces: &[WorkspaceInfo]) -> SystemTrayMenu {
+    if workspaces.len() < 2 {
+        return menu;
+    }
+
+    let Some(active) = workspaces.iter().find(|w| w.active) else {
+        return menu;
+    };
+    let mut workspace_menu = SystemTrayMenu::new();
+    for workspace in workspaces {
+        let item = CustomMenuItem::new(format!("workspace_{}", workspace.name), &workspace.name);
+        workspace_menu = workspace_menu.add_item(if workspace.active {
+            item.selected().disabled()
+        } else {
+            item
+        });
+    }
+    menu.add_native_item(SystemTrayMenuItem::Separator)
+        .add_submenu(SystemTraySubmenu::new(
+            format!("Workspace: {}", active.name),
+            workspace_menu,
+        ))
+}
+
+// Keep the tray in sync with the manager, the menu is rebuilt whenever a node, group,
+// workspace or setting changes and the metrics item is updated in place
+pub fn subscribe_to_manager_events(
+    app_handle: &AppHandle,
+    manager: &NodeManager,
+) -> Result<(), eyre::Report> {
+    let app_handle = app_handle.clone();
+    let subscriber = manager.clone();
+    manager.events.subscribe(move |event| match event {
+        // Only changes to what the menu lists rebuild it, output and metrics arrive continuously
+        ManagerEvent::Output { .. }
+        | ManagerEvent::ContextEvent { .. }
+        | ManagerEvent::SettingsChanged
+        | ManagerEvent::RunAppOnStartupChanged { .. } => {}
+        ManagerEvent::Metrics { node_name, sample } => {
+            if let Some(item) = app_handle
+                .tray_handle()
+                .try_get_item(&format!("metrics_{}", node_name))
+            {
+                let _ = item.set_title(format_metrics_summary(sample));
+            }
+        }
+        ManagerEvent::Node(_)
+        | ManagerEvent::HealthChanged(_)
+        | ManagerEvent::WorkspaceChanged { .. }
+        | ManagerEvent::GroupsChanged => {
+            if let Err(e) = update_tray_menu(&app_handle, &subscriber) {
+                log::error!(operation = "update_tray_menu"; "Failed to update tray menu: {:?}", e);
+            }
+        }
+    })
+}
+
 pub fn handle_tray_click(app_handle: &AppHandle, menu_id: &str) -> Result<(), eyre::Report> {
-    let mut parts = menu_id.split('_');
-    match (parts.next(), parts.next()) {
-        (Some("show"), Some("window")) => show_main_window(app_handle),
-        (Some(action), Some(node)) => handle_tray_action(app_handle, action, node),
-        (Some("quit"), None) => {
+    match menu_id.split_once('_') {
+        Some(("show", "window")) => show_main_window(app_handle),
+        Some(("groupstart" | "groupstop" | "grouprestart", group)) => {
+            handle_group_action(app_handle, menu_id, group)
+        }
+        Some(("workspace", workspace)) => {
+            switch_workspace(&app_handle.state::<NodeManager>(), workspace.to_string())?;
+            Ok(())
+        }
+        Some((action, node)) => handle_tray_action(app_handle, action, node),
+        None if menu_id == "quit" => {
             // Stop all nodes and exit the application
             let app_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = stop_all_nodes(app_handle.state::<AppState>()).await {
-                    eprintln!("Failed to stop all nodes: {}", e);
+                if let Err(e) = stop_all_nodes(&app_handle.state::<NodeManager>()).await {
+                    log::error!(operation = "stop_all_nodes"; "Failed to stop all nodes: {}", e);
                 }
                 app_handle.exit(0);
             });
@@ -156,7 +305,18 @@ pub fn handle_tray_action(
         "config" | "logs" | "delete" => emit_trigger_action(&window, node, action, "")?,
         "show" => emit_trigger_action(&window, node, "", "show")?,
         "dashboard" => {
-            open_admin_dashboard(app_handle.clone(), node.to_string())?;
+            open_admin_dashboard(&app_handle.state::<NodeManager>(), node.to_string())?;
+        }
+        "copypeer" | "copyaddr" => {
+            let peer_info = get_node_peer_info(node, &app_handle.state::<NodeManager>())?;
+            let text = if action == "copypeer" {
+                peer_info.peer_id
+            } else {
+                peer_info.multiaddrs.into_iter().next()
+            };
+            if let Some(text) = text {
+                app_handle.clipboard_manager().write_text(text)?;
+            }
         }
         _ => {}
     }
@@ -164,6 +324,30 @@ pub fn handle_tray_action(
     Ok(())
 }
 
+fn handle_group_action(
+    app_handle: &AppHandle,
+    menu_id: &str,
+    group: &str,
+) -> Result<(), eyre::Report> {
+    let app_handle = app_handle.clone();
+    let menu_id = menu_id.to_string();
+    let group = group.to_string();
+    tauri::async_runtime::spawn(async move {
+        let manager = app_handle.state::<NodeManager>();
+        let result = if menu_id.starts_with("groupstart_") {
+            start_group(&manager, group.clone()).await
+        } else if menu_id.starts_with("groupstop_") {
+            stop_group(&manager, group.clone()).await
+        } else {
+            restart_group(&manager, group.clone()).await
+        };
+        if let Err(e) = result {
+            log::error!(group = group.as_str(), operation = "group_action"; "Failed to handle action for group {}: {:?}", group, e);
+        }
+    });
+    Ok(())
+}
+
 fn get_main_window(app_handle: &AppHandle) -> Result<tauri::Window, eyre::Report> {
     app_handle
         .get_window("main")
@@ -1,10 +1,12 @@
+use crate::error::ManagerError;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
+use std::path::PathBuf;
 use std::process::Child;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
-use tauri_plugin_store::Store;
+use std::time::Instant;
 
 #[derive(Debug)]
 pub struct NodeProcess {
@@ -14,14 +16,18 @@ pub struct NodeProcess {
     pub log_file: Option<File>,
 }
 
-pub struct NodeManager {
-    pub nodes: HashMap<String, NodeProcess>,
+// A relay of context events from a node's WebSocket endpoint, the relay task is
+// stopped when the subscription is dropped
+#[derive(Debug)]
+pub struct NodeSubscription {
+    pub status: Arc<Mutex<SubscriptionStatus>>,
+    pub task: tokio::task::JoinHandle<()>,
 }
 
-pub struct AppState {
-    pub node_manager: Arc<Mutex<NodeManager>>,
-    pub app_handle: tauri::AppHandle,
-    pub store: Mutex<Store<tauri::Wry>>,
+impl Drop for NodeSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -29,6 +35,8 @@ pub struct OperationResult<T = ()> {
     pub success: bool,
     pub message: String,
     pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ManagerError>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -38,6 +46,18 @@ pub struct NodeInfo {
     pub external_node: bool,
     pub run_on_startup: bool,
     pub node_ports: NodeConfig,
+    pub health: HealthStatus,
+    pub peer_info: NodePeerInfo,
+}
+
+// Identity and addresses of a node, read from its config.toml
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodePeerInfo {
+    pub peer_id: Option<String>,
+    // Swarm listen addresses, ending in /p2p/<peer id> when the peer id is known
+    pub multiaddrs: Vec<String>,
+    pub dashboard_url: String,
+    pub jsonrpc_url: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -45,3 +65,506 @@ pub struct NodeConfig {
     pub server_port: u16,
     pub swarm_port: u16,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodeGroup {
+    pub name: String,
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupMember {
+    pub node_name: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub wait_until_ready: bool,
+}
+
+impl NodeGroup {
+    pub fn member(&self, node_name: &str) -> Option<&GroupMember> {
+        self.members.iter().find(|m| m.node_name == node_name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Stopped,
+    Starting,
+    Healthy,
+    Degraded,
+    Unreachable,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeHealth {
+    pub status: HealthStatus,
+    pub started_at: Option<Instant>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthChangedEvent {
+    pub node_name: String,
+    pub previous: HealthStatus,
+    pub status: HealthStatus,
+}
+
+// Node lifecycle changes, serialized with a `type` tag next to their fields,
+// e.g. {"type": "exited", "node_name": "node1", "exit_code": 1}
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    Created {
+        node_name: String,
+    },
+    Started {
+        node_name: String,
+    },
+    Ready {
+        node_name: String,
+    },
+    // The process ended without being stopped, the exit code is missing if it was killed by a signal
+    Exited {
+        node_name: String,
+        exit_code: Option<i32>,
+    },
+    Stopped {
+        node_name: String,
+    },
+    // The node could not be started, e.g. because one of its ports is in use
+    StartFailed {
+        node_name: String,
+        error: ManagerError,
+    },
+    Renamed {
+        previous_name: String,
+        node_name: String,
+    },
+    Deleted {
+        node_name: String,
+    },
+    ConfigChanged {
+        node_name: String,
+    },
+}
+
+impl NodeEvent {
+    pub fn node_name(&self) -> &str {
+        match self {
+            NodeEvent::Created { node_name }
+            | NodeEvent::Started { node_name }
+            | NodeEvent::Ready { node_name }
+            | NodeEvent::Exited { node_name, .. }
+            | NodeEvent::Stopped { node_name }
+            | NodeEvent::StartFailed { node_name, .. }
+            | NodeEvent::Renamed { node_name, .. }
+            | NodeEvent::Deleted { node_name }
+            | NodeEvent::ConfigChanged { node_name } => node_name,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NodeMetricsSample {
+    pub timestamp: i64,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub disk_read_bytes: u64,
+    pub disk_written_bytes: u64,
+    pub data_dir_bytes: u64,
+    pub open_files: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SubscriptionStatus {
+    pub node_name: String,
+    pub context_ids: Vec<String>,
+    pub connected: bool,
+    // Why the last connection attempt failed or the connection was dropped
+    pub last_error: Option<String>,
+}
+
+// An event of a subscribed context received from a node
+#[derive(Serialize, Debug, Clone)]
+pub struct ContextEvent {
+    pub timestamp: i64,
+    pub context_id: Option<String>,
+    // e.g. StateMutation or ExecutionEvent
+    pub event_type: Option<String>,
+    pub data: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcRequestKind {
+    Query,
+    Mutate,
+}
+
+// A call of a context method through the JSON-RPC endpoint of a node
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcRequest {
+    pub kind: RpcRequestKind,
+    pub context_id: String,
+    pub method: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+    // Identity of the context member the method is executed as
+    pub executor_public_key: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcResponse {
+    // Return value of the method, null for methods without one
+    pub output: Option<serde_json::Value>,
+    // Error reported by the node, e.g. the method failed or the context does not exist
+    pub error: Option<serde_json::Value>,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcHistoryEntry {
+    pub id: u64,
+    pub timestamp: i64,
+    pub request: RpcRequest,
+    pub response: Option<RpcResponse>,
+    // Set when no response was received, e.g. the node could not be reached
+    pub failure: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResourceLimits {
+    pub max_memory_mb: Option<u64>,
+    pub nice: Option<i32>,
+    pub max_open_files: Option<u64>,
+    pub soft_memory_mb: Option<u64>,
+    pub soft_cpu_percent: Option<f32>,
+    #[serde(default)]
+    pub watchdog_action: WatchdogAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchdogAction {
+    #[default]
+    Stop,
+    Restart,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LaunchProfile {
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub init_args: Vec<String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Settings {
+    pub version: u32,
+    #[serde(default)]
+    pub app: AppSettings,
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, WorkspaceSettings>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppSettings {
+    #[serde(default = "default_run_app_on_startup")]
+    pub run_app_on_startup: bool,
+    #[serde(default = "default_workspace")]
+    pub active_workspace: String,
+    // Directory for scheduled snapshots, defaults to the app's backups directory
+    #[serde(default)]
+    pub backup_directory: Option<PathBuf>,
+    // Days a deleted node is kept in the trash before it is purged
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    #[serde(default)]
+    pub control_api: ControlApiSettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    // Least severe level written to the application log
+    #[serde(default)]
+    pub log_level: AppLogLevel,
+    #[serde(default)]
+    pub redaction: RedactionSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            run_app_on_startup: default_run_app_on_startup(),
+            active_workspace: default_workspace(),
+            backup_directory: None,
+            trash_retention_days: default_trash_retention_days(),
+            control_api: ControlApiSettings::default(),
+            notifications: NotificationSettings::default(),
+            log_level: AppLogLevel::default(),
+            redaction: RedactionSettings::default(),
+        }
+    }
+}
+
+// Masking of secrets in the node logs and output. Exports are always redacted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedactionSettings {
+    #[serde(default = "default_redaction_enabled")]
+    pub enabled: bool,
+    // Regular expressions masked in addition to the built-in ones
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_redaction_enabled(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+fn default_redaction_enabled() -> bool {
+    true
+}
+
+// Local control API for scripts, only served on the loopback interface when enabled
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ControlApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_control_api_port")]
+    pub port: u16,
+}
+
+impl Default for ControlApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_control_api_port(),
+        }
+    }
+}
+
+fn default_control_api_port() -> u16 {
+    2540
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AppLogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+// An entry of the application log, with the node and operation it concerns in its context
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppLogEntry {
+    pub timestamp: String,
+    pub level: AppLogLevel,
+    pub message: String,
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    // The node process ended without being stopped
+    NodeExited,
+    // The node could not be started because one of its ports is in use
+    PortConflict,
+    // The node turned degraded or unreachable
+    Unhealthy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationSettings {
+    #[serde(default = "default_notifications_enabled")]
+    pub enabled: bool,
+    // Kinds notified about for nodes without their own selection
+    #[serde(default = "default_notification_kinds")]
+    pub kinds: Vec<NotificationKind>,
+    // Minimum time between two notifications of the same kind for a node
+    #[serde(default = "default_notification_interval_secs")]
+    pub min_interval_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_notifications_enabled(),
+            kinds: default_notification_kinds(),
+            min_interval_secs: default_notification_interval_secs(),
+        }
+    }
+}
+
+fn default_notifications_enabled() -> bool {
+    true
+}
+
+fn default_notification_kinds() -> Vec<NotificationKind> {
+    vec![
+        NotificationKind::NodeExited,
+        NotificationKind::PortConflict,
+        NotificationKind::Unhealthy,
+    ]
+}
+
+fn default_notification_interval_secs() -> u64 {
+    60
+}
+
+fn default_run_app_on_startup() -> bool {
+    true
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+pub fn default_workspace() -> String {
+    "default".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WorkspaceSettings {
+    // Directory holding the workspace's nodes, the app's nodes directory if not set
+    #[serde(default)]
+    pub home: Option<PathBuf>,
+    #[serde(default)]
+    pub node_groups: Vec<NodeGroup>,
+    #[serde(default)]
+    pub nodes: BTreeMap<String, NodeSettings>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub home: PathBuf,
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NodeSettings {
+    #[serde(default)]
+    pub run_on_startup: bool,
+    #[serde(default)]
+    pub resource_limits: ResourceLimits,
+    #[serde(default)]
+    pub launch_profile: LaunchProfile,
+    #[serde(default)]
+    pub backup_schedule: Option<BackupSchedule>,
+    // Kinds notified about for this node, the app's selection if not set
+    #[serde(default)]
+    pub notifications: Option<Vec<NotificationKind>>,
+    // Keep input sent to the node out of node.log, e.g. when it prompts for passwords
+    #[serde(default)]
+    pub hide_stdin_in_log: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupSchedule {
+    pub interval: BackupInterval,
+    pub keep_last: u32,
+    // Stop a running node for the duration of the backup, running nodes are skipped
+    // until they are stopped otherwise
+    #[serde(default)]
+    pub stop_running_node: bool,
+    // Unix timestamp (seconds) and outcome of the last scheduled run
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupInterval {
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub node_name: String,
+    pub created_at: String,
+    pub manager_version: String,
+    pub meroctl_version: String,
+    pub node_ports: NodeConfig,
+    pub settings: NodeSettings,
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PortKind {
+    Server,
+    Swarm,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortState {
+    pub kind: PortKind,
+    pub port: u16,
+    pub in_use: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeDiagnostics {
+    pub node_name: String,
+    pub is_running: bool,
+    pub health: HealthStatus,
+    pub ports: Vec<PortState>,
+    // Paths of the node's files inside the bundle
+    pub files: Vec<String>,
+    // What could not be collected, e.g. an unreadable config.toml
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiagnosticSummary {
+    pub created_at: String,
+    pub manager_version: String,
+    pub meroctl_version: String,
+    pub os: String,
+    pub arch: String,
+    pub nodes: Vec<NodeDiagnostics>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashedNode {
+    pub id: String,
+    pub node_name: String,
+    // Workspace the node was deleted from, which it is restored into
+    #[serde(default = "default_workspace")]
+    pub workspace: String,
+    pub deleted_at: i64,
+    pub settings: NodeSettings,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    // Copy the directory into the nodes directory
+    Copy,
+    // Keep the directory where it is and link it from the nodes directory
+    Link,
+}
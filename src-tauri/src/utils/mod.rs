@@ -1,30 +1,63 @@
 use eyre::{bail, eyre, Result};
 use lazy_static::lazy_static;
 use multiaddr::{Multiaddr, Protocol};
+use regex::Regex;
 use serde_json::Value;
 use std::{
-    net::{SocketAddr, TcpStream},
-    path::PathBuf,
-    process::Command,
+    collections::HashSet,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream},
+    path::{Path, PathBuf},
     time::Duration,
 };
-use sysinfo::{ProcessRefreshKind, RefreshKind, System};
-use tauri::regex::Regex;
+use sysinfo::{Process, ProcessRefreshKind, RefreshKind, Signal, System};
 
-use std::{env, fs};
-use tauri::AppHandle;
+use std::fs;
 
-use crate::types::NodeConfig;
+use crate::error::ManagerError;
+use crate::manager::NodeManager;
+use crate::store::get_settings;
+use crate::types::{LaunchProfile, NodeConfig, NodePeerInfo, WorkspaceSettings};
 use shared_utils::determine_bin_data;
 
+#[cfg(feature = "gui")]
 pub mod setup;
 
-pub fn get_nodes_dir(app_handle: &AppHandle) -> PathBuf {
-    app_handle
-        .path_resolver()
-        .app_data_dir()
-        .expect("Failed to get app data dir")
-        .join("nodes")
+// Home directory of the active workspace. Reads the settings, so it must not be
+// called while the store lock is held.
+pub fn get_nodes_dir(manager: &NodeManager) -> PathBuf {
+    get_settings(manager)
+        .ok()
+        .and_then(|settings| settings.workspace().ok().and_then(|w| w.home.clone()))
+        .unwrap_or_else(|| get_default_nodes_dir(manager))
+}
+
+// Home directory of any workspace, not only the active one
+pub fn get_workspace_nodes_dir(manager: &NodeManager, workspace: &WorkspaceSettings) -> PathBuf {
+    workspace
+        .home
+        .clone()
+        .unwrap_or_else(|| get_default_nodes_dir(manager))
+}
+
+pub fn get_default_nodes_dir(manager: &NodeManager) -> PathBuf {
+    default_nodes_dir(&manager.config.data_dir)
+}
+
+// Home directory of the workspaces that have none of their own
+pub fn default_nodes_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("nodes")
+}
+
+pub fn get_backups_dir(manager: &NodeManager) -> PathBuf {
+    manager.config.data_dir.join("backups")
+}
+
+pub fn get_diagnostics_dir(manager: &NodeManager) -> PathBuf {
+    manager.config.data_dir.join("diagnostics")
+}
+
+pub fn get_trash_dir(manager: &NodeManager) -> PathBuf {
+    manager.config.data_dir.join("trash")
 }
 
 lazy_static! {
@@ -35,74 +68,120 @@ pub fn strip_ansi_escapes(s: &str) -> String {
     ANSI_ESCAPE_RE.replace_all(s, "").to_string()
 }
 
-pub fn get_binary_path(app_handle: &AppHandle) -> Result<PathBuf> {
+// Location of the bundled meroctl binary relative to the resource directory
+pub fn binary_relative_path() -> String {
     let (os, arch, _) = determine_bin_data();
+    format!("bin/{}/{}/meroctl", os, arch)
+}
 
-    if cfg!(debug_assertions) {
-        // Development (Debug mode)
-        Ok(env::current_dir()?
-            .join("bin")
-            .join(os)
-            .join(arch)
-            .join("meroctl"))
-    } else {
-        let relative_path = format!("bin/{}/{}/meroctl", os, arch);
-        app_handle
-            .path_resolver()
-            .resolve_resource(&relative_path)
-            .ok_or_else(|| eyre!("Failed to resolve binary resource"))
-    }
-}
-
-pub fn is_node_process_running(app_handle: &AppHandle, node_name: &str) -> Result<bool> {
-    let system = System::new_with_specifics(
-        RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
-    );
-
-    let pattern = format!(
-        r"meroctl.*--node-name\s+\b{}\b.*run",
-        regex_escape(node_name)
-    );
-    let re = Regex::new(&pattern).map_err(|e| eyre!("Failed to create regex: {}", e))?;
-
-    for process in system.processes().values() {
-        let cmd = process
-            .cmd()
-            .iter()
-            .filter_map(|arg| arg.to_str())
-            .collect::<Vec<&str>>()
-            .join(" ");
-
-        if re.is_match(&cmd) {
-            // Print the location of the process's executable
-            if let Some(exe_path) = process.exe() {
-                let binary_path = get_binary_path(app_handle)?;
-                if binary_path.as_path() != exe_path {
-                    bail!(
-                        "Node with name {} is already running outside of the application",
-                        node_name
-                    );
-                }
-                return Ok(true);
+pub fn get_meroctl_version(manager: &NodeManager) -> Result<String> {
+    let output = manager
+        .config
+        .runtime
+        .command()?
+        .arg("--version")
+        .output()
+        .map_err(|e| eyre!("Failed to execute command: {}", e))?;
+    if !output.status.success() {
+        bail!("Failed to get meroctl version");
+    }
+    Ok(strip_ansi_escapes(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+// Snapshot of the running processes, scanning them is expensive so callers checking
+// several nodes should take one and reuse it
+pub fn process_snapshot() -> System {
+    System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::everything()))
+}
+
+pub fn is_node_process_running(manager: &NodeManager, node_name: &str) -> Result<bool> {
+    is_node_process_running_in(&process_snapshot(), manager, node_name)
+}
+
+pub fn is_node_process_running_in(
+    system: &System,
+    manager: &NodeManager,
+    node_name: &str,
+) -> Result<bool> {
+    let nodes_dir = get_nodes_dir(manager);
+    for process in node_processes(system, &nodes_dir, node_name) {
+        // Print the location of the process's executable
+        if let Some(exe_path) = process.exe() {
+            if manager.config.runtime.binary()? != exe_path {
+                bail!(ManagerError::NodeRunningExternally {
+                    node_name: node_name.to_string()
+                });
             }
+            return Ok(true);
         }
     }
 
     Ok(false)
 }
 
-pub fn get_node_ports(node_name: &str, app_handle: &AppHandle) -> Result<NodeConfig> {
-    let config_path = get_nodes_dir(app_handle)
-        .join(node_name)
-        .join("config.toml");
+// The `meroctl run` processes of a node. Node names are only unique within a workspace,
+// so the home directory passed with `--home` has to match as well.
+fn node_processes<'a>(
+    system: &'a System,
+    nodes_dir: &'a Path,
+    node_name: &'a str,
+) -> impl Iterator<Item = &'a Process> + 'a {
+    system.processes().values().filter(move |process| {
+        let cmd = process.cmd();
+        let option = |name: &str| {
+            cmd.iter()
+                .position(|arg| arg == name)
+                .and_then(|index| cmd.get(index + 1))
+        };
+        cmd.first()
+            .is_some_and(|program| program.to_string_lossy().contains("meroctl"))
+            && cmd.iter().any(|arg| arg == "run")
+            && option("--node-name").is_some_and(|name| name == node_name)
+            && option("--home").is_some_and(|home| Path::new(home) == nodes_dir)
+    })
+}
+
+pub fn get_node_ports(node_name: &str, manager: &NodeManager) -> Result<NodeConfig> {
+    let node_dir = get_nodes_dir(manager).join(node_name);
+    if !node_dir.exists() {
+        bail!(ManagerError::NodeNotFound {
+            node_name: node_name.to_string()
+        });
+    }
+    read_node_ports(&node_dir)
+}
+
+pub fn get_node_peer_info(node_name: &str, manager: &NodeManager) -> Result<NodePeerInfo> {
+    let config = get_node_ports(node_name, manager)?;
+    read_node_peer_info(&get_nodes_dir(manager).join(node_name), config.server_port)
+}
+
+fn read_node_config(config_path: &Path) -> Result<Value> {
     let config_content =
-        fs::read_to_string(&config_path).map_err(|e| eyre!("Failed to read config file: {}", e))?;
+        fs::read_to_string(config_path).map_err(|e| eyre!("Failed to read config file: {}", e))?;
+    toml::from_str(&config_content).map_err(|e| {
+        ManagerError::ConfigParse {
+            path: config_path.display().to_string(),
+            reason: e.to_string(),
+        }
+        .into()
+    })
+}
+
+// Read the server and swarm ports from the config.toml in a node directory
+pub fn read_node_ports(node_dir: &Path) -> Result<NodeConfig> {
+    let config_path = node_dir.join("config.toml");
+    let config = read_node_config(&config_path)?;
 
-    let config: Value =
-        toml::from_str(&config_content).map_err(|e| eyre!("Failed to parse TOML: {}", e))?;
+    let config_error = |reason: String| ManagerError::ConfigParse {
+        path: config_path.display().to_string(),
+        reason,
+    };
 
-    let server_port = extract_port(&config, "server")?;
-    let swarm_port = extract_port(&config, "swarm")?;
+    let server_port = extract_port(&config, "server").map_err(|e| config_error(e.to_string()))?;
+    let swarm_port = extract_port(&config, "swarm").map_err(|e| config_error(e.to_string()))?;
 
     Ok(NodeConfig {
         server_port,
@@ -110,6 +189,63 @@ pub fn get_node_ports(node_name: &str, app_handle: &AppHandle) -> Result<NodeCon
     })
 }
 
+// Read the peer id and swarm listen addresses from the config.toml in a node directory
+pub fn read_node_peer_info(node_dir: &Path, server_port: u16) -> Result<NodePeerInfo> {
+    let config = read_node_config(&node_dir.join("config.toml"))?;
+
+    let peer_id = config
+        .get("identity")
+        .and_then(|v| v.get("peer_id"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let multiaddrs = config
+        .get("swarm")
+        .and_then(|v| v.get("listen"))
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .map(dialable_address)
+        .map(|address| match &peer_id {
+            Some(peer_id) => format!("{}/p2p/{}", address, peer_id),
+            None => address,
+        })
+        .collect();
+
+    Ok(NodePeerInfo {
+        peer_id,
+        multiaddrs,
+        dashboard_url: dashboard_url(server_port),
+        jsonrpc_url: jsonrpc_url(server_port),
+    })
+}
+
+// Nodes listen on the unspecified address, which can't be dialed, so peers on this
+// machine are given the loopback address instead
+fn dialable_address(address: &str) -> String {
+    let multiaddr: Multiaddr = match address.parse() {
+        Ok(multiaddr) => multiaddr,
+        Err(_) => return address.to_string(),
+    };
+    multiaddr
+        .iter()
+        .map(|protocol| match protocol {
+            Protocol::Ip4(ip) if ip.is_unspecified() => Protocol::Ip4(Ipv4Addr::LOCALHOST),
+            Protocol::Ip6(ip) if ip.is_unspecified() => Protocol::Ip6(Ipv6Addr::LOCALHOST),
+            protocol => protocol,
+        })
+        .collect::<Multiaddr>()
+        .to_string()
+}
+
+pub fn dashboard_url(server_port: u16) -> String {
+    format!("http://localhost:{}/admin-dashboard", server_port)
+}
+
+pub fn jsonrpc_url(server_port: u16) -> String {
+    format!("http://localhost:{}/jsonrpc", server_port)
+}
+
 fn extract_port(config: &Value, key: &str) -> Result<u16> {
     let listen_addr = config
         .get(key)
@@ -132,19 +268,168 @@ fn extract_port(config: &Value, key: &str) -> Result<u16> {
     bail!("Failed to extract {} port", key);
 }
 
-// Helper function to escape special regex characters
-fn regex_escape(s: &str) -> String {
-    let mut result = String::with_capacity(s.len() * 2);
-    for c in s.chars() {
-        match c {
-            '.' | '+' | '*' | '?' | '^' | '$' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '\\' => {
-                result.push('\\');
-                result.push(c);
+pub fn validate_launch_profile(profile: &LaunchProfile) -> Result<()> {
+    for (key, value) in &profile.env {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            bail!("Invalid environment variable name: {:?}", key);
+        }
+        if value.contains('\0') {
+            bail!("Invalid value for environment variable {}", key);
+        }
+    }
+    for arg in profile.init_args.iter().chain(&profile.run_args) {
+        if arg.contains('\0') {
+            bail!("Invalid argument: {:?}", arg);
+        }
+    }
+    Ok(())
+}
+
+// Total size of all files below a directory, symlinks are not followed
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    let link = fs::read_link(source)
+        .map_err(|e| eyre!("Failed to read link {}: {}", source.display(), e))?;
+    std::os::unix::fs::symlink(link, target)
+        .map_err(|e| eyre!("Failed to copy link {}: {}", source.display(), e))
+}
+
+// Creating links needs extra privileges on Windows, so they are left out of the copy
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, _target: &Path) -> Result<()> {
+    log::warn!(operation = "copy_dir"; "Skipping symbolic link {}", source.display());
+    Ok(())
+}
+
+// Recursively copy a directory. Symbolic links are copied as links rather than
+// followed, and other special files are skipped.
+pub fn copy_dir_all(source: &Path, destination: &Path) -> Result<()> {
+    fs::create_dir_all(destination).map_err(|e| {
+        eyre!(
+            "Failed to create directory {}: {}",
+            destination.display(),
+            e
+        )
+    })?;
+    for entry in fs::read_dir(source)
+        .map_err(|e| eyre!("Failed to read directory {}: {}", source.display(), e))?
+    {
+        let entry = entry.map_err(|e| eyre!("Failed to read directory entry: {}", e))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| eyre!("Failed to read file type: {}", e))?;
+        let target = destination.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| eyre!("Failed to copy {}: {}", entry.path().display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+// Move a file, directory or link. Renaming fails across filesystems, e.g. between a
+// workspace home on an external drive and the app data directory, so the source is
+// then copied and removed instead.
+pub fn move_path(source: &Path, destination: &Path) -> Result<()> {
+    match fs::rename(source, destination) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            copy_and_remove(source, destination)
+        }
+        Err(e) => bail!("Failed to move {}: {}", source.display(), e),
+    }
+}
+
+// Copy a file, directory or link and remove the original once the copy is complete
+pub fn copy_and_remove(source: &Path, destination: &Path) -> Result<()> {
+    let file_type = fs::symlink_metadata(source)
+        .map_err(|e| eyre!("Failed to read file type of {}: {}", source.display(), e))?
+        .file_type();
+    let copied = if file_type.is_symlink() {
+        copy_symlink(source, destination)
+    } else if file_type.is_dir() {
+        copy_dir_all(source, destination)
+    } else {
+        fs::copy(source, destination)
+            .map(|_| ())
+            .map_err(|e| eyre!("Failed to copy {}: {}", source.display(), e))
+    };
+    if let Err(e) = copied {
+        let _ = if file_type.is_dir() {
+            fs::remove_dir_all(destination)
+        } else {
+            fs::remove_file(destination)
+        };
+        return Err(e);
+    }
+
+    if file_type.is_dir() {
+        fs::remove_dir_all(source)
+    } else {
+        fs::remove_file(source)
+    }
+    .map_err(|e| eyre!("Failed to remove {}: {}", source.display(), e))
+}
+
+// Node names are used as directory names in the workspace home
+pub fn validate_node_name(node_name: &str) -> Result<()> {
+    if node_name.trim().is_empty() {
+        bail!("Node name cannot be empty");
+    }
+    if node_name.contains(['/', '\\']) || node_name.contains("..") {
+        bail!("Node name cannot contain '/', '\\' or '..'");
+    }
+    Ok(())
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// Ports assigned to the nodes managed by the application
+pub fn get_assigned_ports(manager: &NodeManager) -> Result<HashSet<u16>> {
+    let mut ports = HashSet::new();
+    for entry in fs::read_dir(get_nodes_dir(manager))
+        .map_err(|e| eyre!("Failed to read nodes directory: {}", e))?
+    {
+        let entry = entry.map_err(|e| eyre!("Failed to read directory entry: {}", e))?;
+        if let Some(node_name) = entry.file_name().to_str() {
+            if let Ok(config) = get_node_ports(node_name, manager) {
+                ports.insert(config.server_port);
+                ports.insert(config.swarm_port);
             }
-            _ => result.push(c),
         }
     }
-    result
+    Ok(ports)
 }
 
 // Check if a node port is in use
@@ -157,27 +442,26 @@ pub fn is_port_in_use(port: u16) -> bool {
 
 // Check if the node ports are available
 pub fn check_ports_availability(config: &NodeConfig) -> Result<()> {
-    if is_port_in_use(config.server_port) || is_port_in_use(config.swarm_port) {
-        return Err(eyre!(
-            "Port {} or {} is already in use",
-            config.server_port,
-            config.swarm_port
-        ));
+    for port in [config.server_port, config.swarm_port] {
+        if is_port_in_use(port) {
+            bail!(ManagerError::PortInUse { port });
+        }
     }
     Ok(())
 }
 
-// Kill the node process
-pub fn kill_node_process(node_name: &str) -> std::io::Result<()> {
-    let output = Command::new("pkill")
-        .args(&["-f", &format!("meroctl.*--node-name {}.*run", node_name)])
-        .output()?;
-
-    if !output.status.success() {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            String::from_utf8_lossy(&output.stderr),
-        ));
+// Kill the node process of the active workspace, for nodes the manager has no handle to
+pub fn kill_node_process(manager: &NodeManager, node_name: &str) -> Result<()> {
+    let system = process_snapshot();
+    let nodes_dir = get_nodes_dir(manager);
+    let mut killed = false;
+    for process in node_processes(&system, &nodes_dir, node_name) {
+        killed |= process
+            .kill_with(Signal::Term)
+            .unwrap_or_else(|| process.kill());
+    }
+    if !killed {
+        bail!("No process found for node {}", node_name);
     }
 
     Ok(())
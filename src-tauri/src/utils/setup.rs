@@ -1,74 +1,133 @@
-use crate::logger::get_node_log_file;
+use crate::events::ManagerEvent;
+use crate::logger::app::init_manager_log;
+use crate::manager::{ManagerConfig, NodeManager};
+use crate::notifications::subscribe_notifications;
 use crate::operations::start_nodes_on_startup;
-use crate::types::{AppState, NodeManager, NodeProcess};
-use crate::utils::get_nodes_dir;
+use crate::runtime::NodeRuntime;
+use crate::utils::binary_relative_path;
 use auto_launch::AutoLaunch;
 use eyre::{eyre, Result};
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::{env, fs};
-use tauri::{App, AppHandle, Manager, State, Wry};
-use tauri_plugin_store::{Store, StoreBuilder};
+use std::env;
+use std::sync::Mutex;
+use tauri::api::notification::Notification;
+use tauri::{App, AppHandle, Manager};
+
+pub const NODE_EVENT: &str = "node-event";
 
 lazy_static! {
     static ref AUTO_LAUNCH: Mutex<Option<AutoLaunch>> = Mutex::new(None);
 }
 
-pub fn setup_store(app: &App) -> Result<Store<Wry>> {
-    let app_data_dir = app
+// Open the node manager on the app data directory, with the meroctl binary bundled
+// as a resource
+pub fn setup_node_manager(app: &App) -> Result<NodeManager> {
+    let data_dir = app
         .path_resolver()
         .app_data_dir()
         .ok_or_else(|| eyre!("Failed to get app data dir"))?;
 
-    if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)
-            .map_err(|e| eyre!("Failed to create app data directory: {}", e))?;
-    }
-
-    let store_path = app_data_dir.join("node_manager.dat");
-    if !store_path.exists() {
-        fs::write(&store_path, "{}").map_err(|e| eyre!("Failed to create store file: {}", e))?;
-    }
-
-    let mut store: Store<Wry> = StoreBuilder::new(app.handle(), store_path).build();
-    store
-        .load()
-        .map_err(|e| eyre!("Failed to load store: {}", e))?;
-
-    Ok(store)
+    let relative_path = binary_relative_path();
+    let binary_path = if cfg!(debug_assertions) {
+        // Development (Debug mode)
+        env::current_dir()
+            .map_err(|e| eyre!("Failed to get current directory: {}", e))?
+            .join(&relative_path)
+    } else {
+        app.path_resolver()
+            .resolve_resource(&relative_path)
+            .unwrap_or_else(|| relative_path.into())
+    };
+
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir,
+        runtime: NodeRuntime::new(binary_path),
+    })?;
+
+    // Send the manager's own log to the app log dir from here on
+    let log_dir = app
+        .path_resolver()
+        .app_log_dir()
+        .ok_or_else(|| eyre!("Failed to get app log dir"))?;
+    init_manager_log(&manager, &log_dir)?;
+    Ok(manager)
 }
 
-pub fn setup_app_state(app_handle: AppHandle, store: Store<Wry>) -> Result<AppState> {
-    let node_manager = Arc::new(Mutex::new(NodeManager {
-        nodes: load_nodes(&app_handle)?,
-    }));
+// Forward manager events to all windows, node lifecycle events as `node-event` and
+// the others on the events the frontend listened to before
+pub fn forward_events_to_windows(app_handle: &AppHandle, manager: &NodeManager) -> Result<()> {
+    let app_handle = app_handle.clone();
+    manager.events.subscribe(move |event| {
+        let result = match event {
+            ManagerEvent::Node(event) => app_handle.emit_all(NODE_EVENT, event),
+            ManagerEvent::Output { node_name, line } => app_handle
+                .emit_all(&format!("node-output-{}", node_name), format!("{}\n", line)),
+            ManagerEvent::HealthChanged(event) => {
+                app_handle.emit_all("node-health-changed", event)
+            }
+            ManagerEvent::Metrics { node_name, sample } => {
+                app_handle.emit_all(&format!("node-metrics-{}", node_name), sample)
+            }
+            ManagerEvent::ContextEvent { node_name, event } => {
+                app_handle.emit_all(&format!("node-context-event-{}", node_name), event)
+            }
+            ManagerEvent::WorkspaceChanged { name } => {
+                app_handle.emit_all("workspace-changed", name)
+            }
+            ManagerEvent::GroupsChanged => app_handle.emit_all("groups-changed", ()),
+            ManagerEvent::SettingsChanged => app_handle.emit_all("settings-changed", ()),
+            ManagerEvent::RunAppOnStartupChanged { .. } => Ok(()),
+        };
+        if let Err(e) = result {
+            log::error!(operation = "emit_event"; "Failed to emit event {:?}: {}", event, e);
+        }
+    })
+}
 
-    Ok(AppState {
-        store: Mutex::new(store),
-        app_handle,
-        node_manager,
+// Apply run_app_on_startup whenever the settings change it, whether from a window or
+// the control API
+pub fn sync_run_app_on_startup(app_handle: &AppHandle, manager: &NodeManager) -> Result<()> {
+    let app_handle = app_handle.clone();
+    manager.events.subscribe(move |event| {
+        if let ManagerEvent::RunAppOnStartupChanged { enabled } = event {
+            if let Err(e) = apply_run_app_on_startup(&app_handle, *enabled) {
+                log::error!(
+                    operation = "apply_run_app_on_startup";
+                    "Failed to apply run app on startup: {:?}", e
+                );
+            }
+        }
     })
 }
 
-pub fn setup_app_folders(app_handle: &AppHandle) -> Result<()> {
-    let nodes_dir = get_nodes_dir(app_handle);
-    if !nodes_dir.exists() {
-        fs::create_dir_all(nodes_dir).map_err(|e| eyre!("Failed to create nodes directory: {}", e))?;
-    }
-    Ok(())
+// Raise native desktop notifications for node crashes, port conflicts and health changes
+pub fn show_desktop_notifications(app_handle: &AppHandle, manager: &NodeManager) -> Result<()> {
+    let identifier = app_handle.config().tauri.bundle.identifier.clone();
+    subscribe_notifications(manager, move |notification| {
+        if let Err(e) = Notification::new(&identifier)
+            .title(&notification.title)
+            .body(&notification.body)
+            .show()
+        {
+            log::error!(
+                node = notification.node_name.as_str(), operation = "notify";
+                "Failed to show notification: {}", e
+            );
+        }
+    })
 }
 
 // Run start_nodes_on_startup
-pub fn run_nodes_on_startup(state: &State<'_, AppState>) -> Result<(), Box<dyn std::error::Error>> {
-    let app_handle_clone = state.app_handle.clone();
+pub fn run_nodes_on_startup(manager: &NodeManager) {
+    let manager = manager.clone();
     tauri::async_runtime::spawn(async move {
-        let state: State<'_, AppState> = app_handle_clone.state::<AppState>().clone();
-        if let Err(e) = start_nodes_on_startup(state).await {
-            eprintln!("Error starting nodes on startup: {:?}", e);
+        if let Err(e) = start_nodes_on_startup(&manager).await {
+            log::error!(
+                operation = "start_nodes_on_startup";
+                "Error starting nodes on startup: {:?}", e
+            );
         }
     });
-    Ok(())
 }
 
 pub fn setup_auto_launch(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -81,38 +140,23 @@ pub fn disable_auto_launch(app: &AppHandle) -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
-fn load_nodes(app_handle: &AppHandle) -> Result<HashMap<String, NodeProcess>> {
-    let mut nodes = HashMap::new();
-
-    let nodes_dir = get_nodes_dir(app_handle);
-    if !nodes_dir.exists() {
-        return Ok(HashMap::new());
+// Enable or disable launching the app on OS startup and reflect it in the window menu
+pub fn apply_run_app_on_startup(app_handle: &AppHandle, enabled: bool) -> Result<()> {
+    if enabled {
+        setup_auto_launch(app_handle).map_err(|e| eyre!("Failed to setup auto launch: {}", e))?;
+    } else {
+        disable_auto_launch(app_handle)
+            .map_err(|e| eyre!("Failed to disable auto launch: {}", e))?;
     }
 
-    // Iterate over the directory entries
-    for entry in fs::read_dir(nodes_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        // Check if the entry is a directory
-        if path.is_dir() {
-            if let Some(node_name) = path.file_name().and_then(|name| name.to_str()) {
-                let log_file = get_node_log_file(app_handle, node_name)?;
-
-                nodes.insert(
-                    node_name.to_string(),
-                    NodeProcess {
-                        process: None,
-                        stdin: None,
-                        output: Arc::new(Mutex::new(String::new())),
-                        log_file: Some(log_file),
-                    },
-                );
-            }
-        }
+    for window in app_handle.windows().values() {
+        window
+            .menu_handle()
+            .get_item("run_on_startup")
+            .set_selected(enabled)
+            .map_err(|e| eyre!("Failed to update menu item state: {}", e))?;
     }
-
-    Ok(nodes)
+    Ok(())
 }
 
 fn get_auto_launch(app: &AppHandle) -> Result<AutoLaunch, Box<dyn std::error::Error>> {
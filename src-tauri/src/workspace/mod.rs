@@ -0,0 +1,135 @@
+use crate::{
+    events::ManagerEvent,
+    manager::{load_workspace_nodes, setup_app_folders, NodeManager},
+    store::{get_settings, modify_settings},
+    types::{WorkspaceInfo, WorkspaceSettings},
+    utils::get_workspace_nodes_dir,
+};
+use eyre::{bail, eyre, Result};
+use std::path::PathBuf;
+
+pub fn list_workspaces(manager: &NodeManager) -> Result<Vec<WorkspaceInfo>> {
+    let settings = get_settings(manager)?;
+    Ok(settings
+        .workspaces
+        .iter()
+        .map(|(name, workspace)| WorkspaceInfo {
+            name: name.clone(),
+            home: get_workspace_nodes_dir(manager, workspace),
+            active: *name == settings.app.active_workspace,
+        })
+        .collect())
+}
+
+// Create a workspace or change the home directory of an existing one. The home
+// directory of the active workspace cannot be changed while it is in use.
+pub fn save_workspace(manager: &NodeManager, name: String, home: Option<PathBuf>) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("Workspace name cannot be empty");
+    }
+    if let Some(home) = &home {
+        if !home.is_absolute() {
+            bail!("Workspace home must be an absolute path");
+        }
+    }
+
+    modify_settings(manager, |settings| {
+        if name == settings.app.active_workspace {
+            bail!("Cannot change the home directory of the active workspace");
+        }
+        settings
+            .workspaces
+            .entry(name.clone())
+            .or_insert_with(WorkspaceSettings::default)
+            .home = home;
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+// Remove a workspace from the settings. Its home directory and nodes are left on disk.
+pub fn delete_workspace(manager: &NodeManager, name: String) -> Result<()> {
+    modify_settings(manager, |settings| {
+        if name == settings.app.active_workspace {
+            bail!("Cannot delete the active workspace");
+        }
+        if settings.workspaces.remove(&name).is_none() {
+            bail!("Workspace not found: {}", name);
+        }
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn running_node_names(manager: &NodeManager) -> Result<Vec<String>> {
+    let mut nodes = manager
+        .nodes
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node manager: {}", e))?;
+    Ok(nodes
+        .iter_mut()
+        .filter_map(|(node_name, node_process)| {
+            let process = node_process.process.as_mut()?;
+            match process.try_wait() {
+                Ok(None) => Some(node_name.clone()),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+// Make another workspace active and reload the nodes, tray and node state from its home
+pub fn switch_workspace(manager: &NodeManager, name: String) -> Result<()> {
+    let running_nodes = running_node_names(manager)?;
+    if !running_nodes.is_empty() {
+        bail!(
+            "Stop the running nodes before switching workspace: {}",
+            running_nodes.join(", ")
+        );
+    }
+
+    modify_settings(manager, |settings| {
+        if !settings.workspaces.contains_key(&name) {
+            bail!("Workspace not found: {}", name);
+        }
+        settings.app.active_workspace = name.clone();
+        Ok(())
+    })?;
+
+    manager
+        .health
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node health: {}", e))?
+        .clear();
+    manager
+        .metrics
+        .lock()
+        .map_err(|e| eyre!("Failed to lock node metrics: {}", e))?
+        .clear();
+    manager
+        .rpc_history
+        .lock()
+        .map_err(|e| eyre!("Failed to lock request history: {}", e))?
+        .clear();
+    manager
+        .subscriptions
+        .lock()
+        .map_err(|e| eyre!("Failed to lock subscriptions: {}", e))?
+        .clear();
+    manager
+        .context_events
+        .lock()
+        .map_err(|e| eyre!("Failed to lock context events: {}", e))?
+        .clear();
+
+    setup_app_folders(manager)?;
+    load_workspace_nodes(manager)?;
+
+    manager
+        .events
+        .publish(ManagerEvent::WorkspaceChanged { name })?;
+
+    Ok(())
+}
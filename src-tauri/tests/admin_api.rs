@@ -0,0 +1,213 @@
+// The admin client against a mock of the node's admin API
+mod common;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{Method, StatusCode, Uri},
+    response::IntoResponse,
+    Json, Router,
+};
+use common::{create_test_node, error_code, free_port, open_manager, unique_node_name};
+use node_multiplatform_tauri::admin::AdminClient;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+type Requests = Arc<Mutex<Vec<(Method, String, Value)>>>;
+
+async fn mock_admin_api(
+    State(requests): State<Requests>,
+    method: Method,
+    uri: Uri,
+    body: Bytes,
+) -> impl IntoResponse {
+    let path = uri.path().to_string();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    requests
+        .lock()
+        .unwrap()
+        .push((method.clone(), path.clone(), body));
+
+    let data = match (method, path.as_str()) {
+        (Method::GET, "/admin-api/health") => json!({ "status": "alive" }),
+        (Method::GET, "/admin-api/dev/applications") => json!({
+            "apps": [{
+                "id": "app1",
+                "blob": "blob1",
+                "size": 1024,
+                "source": "file:///apps/kv_store.wasm",
+                "metadata": [1, 2]
+            }]
+        }),
+        (Method::POST, "/admin-api/dev/install-application")
+        | (Method::POST, "/admin-api/dev/install-dev-application") => {
+            json!({ "applicationId": "app1" })
+        }
+        (Method::GET, "/admin-api/dev/contexts") => json!({
+            "contexts": [{ "id": "ctx1", "applicationId": "app1", "rootHash": "hash1" }]
+        }),
+        (Method::POST, "/admin-api/dev/contexts")
+        | (Method::POST, "/admin-api/dev/contexts/join") => {
+            json!({ "contextId": "ctx1", "memberPublicKey": "member1" })
+        }
+        (Method::DELETE, "/admin-api/dev/contexts/ctx1") => json!({ "isDeleted": true }),
+        (Method::POST, "/admin-api/dev/contexts/invite") => json!("invitation1"),
+        (Method::GET, "/admin-api/dev/contexts/ctx1/identities") => {
+            json!({ "identities": ["member1", "member2"] })
+        }
+        (Method::POST, "/admin-api/dev/identity/context") => {
+            json!({ "publicKey": "public1", "privateKey": "private1" })
+        }
+        _ => return (StatusCode::NOT_FOUND, "context not found").into_response(),
+    };
+    Json(json!({ "data": data })).into_response()
+}
+
+async fn start_mock() -> (AdminClient, Requests) {
+    let requests = Requests::default();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let router = Router::new()
+        .fallback(mock_admin_api)
+        .with_state(Arc::clone(&requests));
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (AdminClient::new(url).unwrap(), requests)
+}
+
+fn last_request(requests: &Requests) -> (Method, String, Value) {
+    requests.lock().unwrap().last().cloned().expect("a request")
+}
+
+#[tokio::test]
+async fn reads_applications_and_contexts() {
+    let (client, _requests) = start_mock().await;
+
+    assert_eq!(client.health().await.unwrap().status, "alive");
+
+    let apps = client.list_applications().await.unwrap();
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].id, "app1");
+    assert_eq!(apps[0].size, 1024);
+    assert_eq!(apps[0].metadata, vec![1, 2]);
+
+    let contexts = client.list_contexts().await.unwrap();
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(contexts[0].application_id, "app1");
+    assert_eq!(contexts[0].root_hash.as_deref(), Some("hash1"));
+
+    let identities = client.list_context_identities("ctx1").await.unwrap();
+    assert_eq!(identities, vec!["member1", "member2"]);
+}
+
+#[tokio::test]
+async fn sends_application_and_context_requests() {
+    let (client, requests) = start_mock().await;
+
+    let application_id = client
+        .install_application(
+            "https://example.com/app.wasm".to_string(),
+            Some("hash1".to_string()),
+            vec![7],
+        )
+        .await
+        .unwrap();
+    assert_eq!(application_id, "app1");
+    assert_eq!(
+        last_request(&requests),
+        (
+            Method::POST,
+            "/admin-api/dev/install-application".to_string(),
+            json!({ "url": "https://example.com/app.wasm", "hash": "hash1", "metadata": [7] })
+        )
+    );
+
+    client
+        .install_dev_application(PathBuf::from("/apps/kv_store.wasm"), Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        last_request(&requests).2,
+        json!({ "path": "/apps/kv_store.wasm", "metadata": [] })
+    );
+
+    let membership = client
+        .create_context("app1".to_string(), b"{}".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(membership.context_id, "ctx1");
+    assert_eq!(membership.member_public_key, "member1");
+    assert_eq!(
+        last_request(&requests).2,
+        json!({ "applicationId": "app1", "initializationParams": [123, 125] })
+    );
+
+    let invitation = client
+        .invite_to_context(
+            "ctx1".to_string(),
+            "member1".to_string(),
+            "public1".to_string(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(invitation, "invitation1");
+    assert_eq!(
+        last_request(&requests).2,
+        json!({ "contextId": "ctx1", "inviterId": "member1", "inviteeId": "public1" })
+    );
+
+    let identity = client.generate_context_identity().await.unwrap();
+    assert_eq!(identity.private_key, "private1");
+
+    let membership = client
+        .join_context(identity.private_key, invitation)
+        .await
+        .unwrap();
+    assert_eq!(membership.context_id, "ctx1");
+    assert_eq!(
+        last_request(&requests).2,
+        json!({ "privateKey": "private1", "invitationPayload": "invitation1" })
+    );
+
+    client.delete_context("ctx1").await.unwrap();
+    let (method, path, _) = last_request(&requests);
+    assert_eq!(method, Method::DELETE);
+    assert_eq!(path, "/admin-api/dev/contexts/ctx1");
+}
+
+#[tokio::test]
+async fn reports_failed_requests() {
+    let (client, _requests) = start_mock().await;
+
+    let error = client.delete_context("missing").await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Admin API request failed with 404 Not Found: context not found"
+    );
+
+    let unreachable = AdminClient::new(format!("http://127.0.0.1:{}", free_port())).unwrap();
+    assert!(unreachable
+        .health()
+        .await
+        .unwrap_err()
+        .to_string()
+        .starts_with("Failed to reach admin API"));
+}
+
+#[tokio::test]
+async fn connects_to_running_nodes_only() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("admin");
+    create_test_node(&manager, &node_name, false).await;
+
+    let code = |result: eyre::Result<AdminClient>| error_code(&result.err().expect("an error"));
+    assert_eq!(
+        code(AdminClient::for_node(&manager, &node_name)),
+        "NODE_NOT_RUNNING"
+    );
+    assert_eq!(
+        code(AdminClient::for_node(&manager, "missing")),
+        "NODE_NOT_FOUND"
+    );
+}
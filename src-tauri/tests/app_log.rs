@@ -0,0 +1,63 @@
+// The manager's application log. The logger is global to the process, so everything
+// is checked in a single test.
+mod common;
+
+use common::open_manager;
+use node_multiplatform_tauri::logger::app::{
+    app_log_dir, format_entry, init_manager_log, read_app_log, APP_LOG_FILE,
+};
+use node_multiplatform_tauri::operations::update_app_settings;
+use node_multiplatform_tauri::store::get_settings;
+use node_multiplatform_tauri::types::AppLogLevel;
+use std::fs;
+
+#[test]
+fn records_entries_with_levels_and_context() {
+    let (_data_dir, manager) = open_manager();
+    let log_dir = tempfile::tempdir().unwrap();
+    init_manager_log(&manager, log_dir.path()).unwrap();
+    assert_eq!(app_log_dir(), Some(log_dir.path()));
+    assert!(init_manager_log(&manager, log_dir.path()).is_err());
+
+    log::error!(node = "node1", operation = "stop_node"; "Failed to stop node");
+    log::warn!(operation = "purge_trash"; "Skipping invalid trash entry");
+    log::info!(node = "node2", operation = "node_event"; "Node started");
+    // Below the default level
+    log::debug!("Not recorded");
+
+    let entries = read_app_log(None, None).unwrap();
+    let messages: Vec<_> = entries.iter().map(|entry| entry.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Failed to stop node",
+            "Skipping invalid trash entry",
+            "Node started"
+        ]
+    );
+    assert_eq!(entries[0].level, AppLogLevel::Error);
+    assert_eq!(entries[0].context["node"], "node1");
+    assert_eq!(entries[0].context["operation"], "stop_node");
+    assert!(format_entry(&entries[0])
+        .ends_with("ERROR [node=node1 operation=stop_node] Failed to stop node"));
+
+    let warnings = read_app_log(Some(AppLogLevel::Warn), None).unwrap();
+    assert_eq!(warnings.len(), 2);
+    let last = read_app_log(None, Some(1)).unwrap();
+    assert_eq!(last[0].message, "Node started");
+
+    // The level follows the app settings
+    let mut settings = get_settings(&manager).unwrap();
+    settings.app.log_level = AppLogLevel::Error;
+    update_app_settings(&manager, settings).unwrap();
+    log::warn!("Not recorded either");
+    assert_eq!(read_app_log(None, None).unwrap().len(), 3);
+
+    // Lines that are not entries are skipped
+    fs::write(
+        log_dir.path().join(format!("{}.1", APP_LOG_FILE)),
+        "not an entry\n",
+    )
+    .unwrap();
+    assert_eq!(read_app_log(None, None).unwrap().len(), 3);
+}
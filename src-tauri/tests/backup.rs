@@ -0,0 +1,355 @@
+// Backup archives of nodes, restored under a new name and checked against tampering
+mod common;
+
+use common::{create_test_node, free_port, open_manager, unique_node_name, wait_for_output};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use node_multiplatform_tauri::backup::schedule::{is_backup_due, prune_snapshots, run_due_backups};
+use node_multiplatform_tauri::backup::{backup_node, restore_node, unpack_archive};
+use node_multiplatform_tauri::logger::read_log_file;
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::operations::{start_node, stop_node_process};
+use node_multiplatform_tauri::store::{
+    get_node_settings, modify_settings, update_run_node_on_startup,
+};
+use node_multiplatform_tauri::types::{BackupInterval, BackupSchedule};
+use node_multiplatform_tauri::utils::{
+    get_backups_dir, get_node_ports, get_nodes_dir, is_node_process_running,
+};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tar::{Archive, Builder, Header};
+
+async fn create_backed_up_node(manager: &NodeManager, prefix: &str) -> (String, PathBuf) {
+    let node_name = unique_node_name(prefix);
+    create_test_node(manager, &node_name, false).await;
+    update_run_node_on_startup(manager, &node_name, true).unwrap();
+    let data_dir = get_nodes_dir(manager).join(&node_name).join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::write(data_dir.join("state.db"), "contexts").unwrap();
+
+    let archive_path = backup_node(manager, node_name.clone(), None).await.unwrap();
+    (node_name, archive_path)
+}
+
+fn append_file(builder: &mut Builder<GzEncoder<File>>, path: &str, data: &[u8]) {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data).unwrap();
+}
+
+// Copy an archive entry by entry, letting the callback change the content of an entry
+// or drop it, and append extra entries at the end
+fn rewrite_archive<F>(source: &Path, destination: &Path, extra: &[(&str, &[u8])], mut modify: F)
+where
+    F: FnMut(&str, Vec<u8>) -> Option<Vec<u8>>,
+{
+    let mut archive = Archive::new(GzDecoder::new(File::open(source).unwrap()));
+    let mut builder = Builder::new(GzEncoder::new(
+        File::create(destination).unwrap(),
+        Compression::default(),
+    ));
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().display().to_string();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        if let Some(data) = modify(&path, data) {
+            append_file(&mut builder, &path, &data);
+        }
+    }
+    for (path, data) in extra {
+        append_file(&mut builder, path, data);
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restores_backup_under_new_name() {
+    let (_data_dir, manager) = open_manager();
+    let (node_name, archive_path) = create_backed_up_node(&manager, "backup").await;
+    assert!(archive_path.starts_with(get_backups_dir(&manager)));
+
+    // The original node still holds its ports, so the restored node gets new ones
+    let restored_name = format!("{}-restored", node_name);
+    assert_eq!(
+        restore_node(
+            &manager,
+            archive_path.clone(),
+            Some(restored_name.clone()),
+            None,
+            None
+        )
+        .unwrap(),
+        restored_name
+    );
+    let nodes_dir = get_nodes_dir(&manager);
+    assert_eq!(
+        fs::read_to_string(nodes_dir.join(&restored_name).join("data/state.db")).unwrap(),
+        "contexts"
+    );
+    let original_ports = get_node_ports(&node_name, &manager).unwrap();
+    let restored_ports = get_node_ports(&restored_name, &manager).unwrap();
+    assert_ne!(restored_ports.server_port, original_ports.server_port);
+    assert_ne!(restored_ports.swarm_port, original_ports.swarm_port);
+    assert_ne!(restored_ports.server_port, restored_ports.swarm_port);
+    assert!(
+        get_node_settings(&manager, &restored_name)
+            .unwrap()
+            .run_on_startup
+    );
+
+    // Existing nodes are not overwritten
+    assert!(restore_node(&manager, archive_path, None, None, None).is_err());
+    let staging_dirs = fs::read_dir(get_backups_dir(&manager))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".restore-")
+        })
+        .count();
+    assert_eq!(staging_dirs, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_conflicting_restore_ports() {
+    let (_data_dir, manager) = open_manager();
+    let (node_name, archive_path) = create_backed_up_node(&manager, "backup-ports").await;
+    let assigned = get_node_ports(&node_name, &manager).unwrap();
+    let restored_name = format!("{}-restored", node_name);
+
+    let port = free_port();
+    assert!(restore_node(
+        &manager,
+        archive_path.clone(),
+        Some(restored_name.clone()),
+        Some(port),
+        Some(port)
+    )
+    .is_err());
+    assert!(restore_node(
+        &manager,
+        archive_path.clone(),
+        Some(restored_name.clone()),
+        Some(assigned.server_port as u32),
+        None
+    )
+    .is_err());
+    assert!(restore_node(
+        &manager,
+        archive_path.clone(),
+        Some(restored_name.clone()),
+        None,
+        Some(assigned.server_port as u32)
+    )
+    .is_err());
+    assert!(!get_nodes_dir(&manager).join(&restored_name).exists());
+
+    // An explicit swarm port is not handed out again as the server port
+    let swarm_port = free_port();
+    restore_node(
+        &manager,
+        archive_path,
+        Some(restored_name.clone()),
+        None,
+        Some(swarm_port),
+    )
+    .unwrap();
+    let restored_ports = get_node_ports(&restored_name, &manager).unwrap();
+    assert_eq!(restored_ports.swarm_port as u32, swarm_port);
+    assert_ne!(restored_ports.server_port as u32, swarm_port);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_tampered_archives() {
+    let (data_dir, manager) = open_manager();
+    let (node_name, archive_path) = create_backed_up_node(&manager, "backup-tampered").await;
+    let tampered = data_dir.path().join("tampered.tar.gz");
+
+    // Changed content fails the checksum
+    rewrite_archive(&archive_path, &tampered, &[], |path, data| {
+        Some(if path == "node/data/state.db" {
+            b"tampered".to_vec()
+        } else {
+            data
+        })
+    });
+    let error = unpack_archive(&tampered, &data_dir.path().join("changed")).unwrap_err();
+    assert_eq!(error.to_string(), "Checksum mismatch for data/state.db");
+
+    // Files missing from the archive or not listed in the manifest are rejected
+    rewrite_archive(&archive_path, &tampered, &[], |path, data| {
+        (path != "node/data/state.db").then_some(data)
+    });
+    let error = unpack_archive(&tampered, &data_dir.path().join("missing")).unwrap_err();
+    assert_eq!(error.to_string(), "Archive is missing node/data/state.db");
+
+    rewrite_archive(
+        &archive_path,
+        &tampered,
+        &[("node/data/injected.sh", b"#!/bin/sh")],
+        |_, data| Some(data),
+    );
+    let error = unpack_archive(&tampered, &data_dir.path().join("unlisted")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Archive contains node/data/injected.sh, which is not listed in its manifest"
+    );
+
+    rewrite_archive(
+        &archive_path,
+        &tampered,
+        &[("config.toml", b"[server]")],
+        |_, data| Some(data),
+    );
+    let staging_dir = data_dir.path().join("outside");
+    assert!(unpack_archive(&tampered, &staging_dir).is_err());
+    assert!(!staging_dir.join("config.toml").exists());
+
+    // Nothing is restored from a tampered archive
+    let restored_name = format!("{}-restored", node_name);
+    assert!(restore_node(&manager, tampered, Some(restored_name.clone()), None, None).is_err());
+    assert!(!get_nodes_dir(&manager).join(&restored_name).exists());
+}
+
+fn schedule(interval: BackupInterval, last_run_at: Option<i64>) -> BackupSchedule {
+    BackupSchedule {
+        interval,
+        keep_last: 2,
+        stop_running_node: false,
+        last_run_at,
+        last_error: None,
+    }
+}
+
+fn snapshots(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn schedules_backups_after_their_interval() {
+    let now = 1_700_000_000;
+    let hour = 60 * 60;
+    assert!(is_backup_due(&schedule(BackupInterval::Hourly, None), now));
+    assert!(!is_backup_due(
+        &schedule(BackupInterval::Hourly, Some(now - hour + 1)),
+        now
+    ));
+    assert!(is_backup_due(
+        &schedule(BackupInterval::Hourly, Some(now - hour)),
+        now
+    ));
+    assert!(!is_backup_due(
+        &schedule(BackupInterval::Daily, Some(now - 23 * hour)),
+        now
+    ));
+    assert!(is_backup_due(
+        &schedule(BackupInterval::Daily, Some(now - 24 * hour)),
+        now
+    ));
+    assert!(!is_backup_due(
+        &schedule(BackupInterval::Weekly, Some(now - 6 * 24 * hour)),
+        now
+    ));
+    // Runs missed while the application was closed are caught up once
+    assert!(is_backup_due(
+        &schedule(BackupInterval::Weekly, Some(now - 30 * 24 * hour)),
+        now
+    ));
+}
+
+#[test]
+fn prunes_oldest_snapshots_of_node() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in [
+        "node-20240103T000000.tar.gz",
+        "node-20240101T000000.tar.gz",
+        "node-20240102T000000.tar.gz",
+        "node-other-20240101T000000.tar.gz",
+        "other-20240101T000000.tar.gz",
+        "node-notes.txt",
+    ] {
+        fs::write(dir.path().join(name), "").unwrap();
+    }
+
+    let removed = prune_snapshots(dir.path(), "node", 2).unwrap();
+    assert_eq!(
+        removed,
+        vec![dir.path().join("node-20240101T000000.tar.gz")]
+    );
+    assert_eq!(
+        snapshots(dir.path()),
+        vec![
+            "node-20240102T000000.tar.gz",
+            "node-20240103T000000.tar.gz",
+            "node-notes.txt",
+            "node-other-20240101T000000.tar.gz",
+            "other-20240101T000000.tar.gz",
+        ]
+    );
+    assert!(prune_snapshots(dir.path(), "node", 2).unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn skips_running_nodes_unless_schedule_stops_them() {
+    let (data_dir, manager) = open_manager();
+    let node_name = unique_node_name("backup-schedule");
+    create_test_node(&manager, &node_name, false).await;
+    let backup_directory = data_dir.path().join("snapshots");
+    let set_schedule = |stop_running_node: bool| {
+        modify_settings(&manager, |settings| {
+            settings.app.backup_directory = Some(backup_directory.clone());
+            settings
+                .workspace_mut()?
+                .nodes
+                .entry(node_name.clone())
+                .or_default()
+                .backup_schedule = Some(BackupSchedule {
+                stop_running_node,
+                ..schedule(BackupInterval::Daily, None)
+            });
+            Ok(())
+        })
+        .unwrap()
+    };
+    let snapshot_dir = backup_directory.join("default").join(&node_name);
+
+    set_schedule(false);
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    run_due_backups(&manager).await.unwrap();
+    run_due_backups(&manager).await.unwrap();
+    assert!(!snapshot_dir.exists());
+    let schedule_after = get_node_settings(&manager, &node_name)
+        .unwrap()
+        .backup_schedule
+        .unwrap();
+    assert_eq!(schedule_after.last_run_at, None);
+    assert!(schedule_after.last_error.is_some());
+    let log = read_log_file(&manager, &node_name).unwrap();
+    assert_eq!(log.matches("skipped, the node is running").count(), 1);
+
+    set_schedule(true);
+    run_due_backups(&manager).await.unwrap();
+    assert_eq!(snapshots(&snapshot_dir).len(), 1);
+    assert!(is_node_process_running(&manager, &node_name).unwrap());
+    let schedule_after = get_node_settings(&manager, &node_name)
+        .unwrap()
+        .backup_schedule
+        .unwrap();
+    assert!(schedule_after.last_run_at.is_some());
+    assert_eq!(schedule_after.last_error, None);
+
+    stop_node_process(&manager, node_name).await.unwrap();
+}
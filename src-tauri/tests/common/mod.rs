@@ -0,0 +1,149 @@
+#![allow(dead_code)]
+
+use node_multiplatform_tauri::error::ManagerError;
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::manager::{ManagerConfig, NodeManager};
+use node_multiplatform_tauri::operations::{create_node, get_node_output};
+use node_multiplatform_tauri::runtime::NodeRuntime;
+use node_multiplatform_tauri::types::LaunchProfile;
+use std::env;
+use std::fs;
+use std::future::Future;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+// The fake meroctl is compiled from tests/support/fake_meroctl.rs once per test binary
+// rather than shipped as a binary of the package. It is built under a temporary name and
+// renamed into place, as the test binaries run in parallel. The file name has to contain
+// `meroctl`, which is how running node processes are found.
+fn fake_meroctl_path() -> &'static Path {
+    static FAKE_MEROCTL: OnceLock<PathBuf> = OnceLock::new();
+    FAKE_MEROCTL.get_or_init(|| {
+        let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+        let binary_path = target_dir.join(format!("fake-meroctl{}", env::consts::EXE_SUFFIX));
+        let build_path = target_dir.join(format!(
+            "fake-meroctl-{}{}",
+            std::process::id(),
+            env::consts::EXE_SUFFIX
+        ));
+        let status = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+            .args(["--edition", "2021", "-o"])
+            .arg(&build_path)
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/support/fake_meroctl.rs"))
+            .status()
+            .expect("run rustc");
+        assert!(status.success(), "failed to compile the fake meroctl");
+        fs::rename(&build_path, &binary_path).expect("move fake meroctl into place");
+        binary_path
+    })
+}
+
+// Runs nodes with the fake meroctl, which is scripted by adding env vars with `with_env`
+pub fn fake_runtime() -> NodeRuntime {
+    NodeRuntime::new(fake_meroctl_path())
+}
+
+// A manager on a fresh data directory, running nodes with the fake meroctl. The
+// directory is removed when the returned guard is dropped.
+pub fn open_manager() -> (TempDir, NodeManager) {
+    open_manager_with(fake_runtime())
+}
+
+pub fn open_manager_with(runtime: NodeRuntime) -> (TempDir, NodeManager) {
+    let data_dir = tempfile::tempdir().expect("create temp dir");
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime,
+    })
+    .expect("open node manager");
+    (data_dir, manager)
+}
+
+// Creates a node on free ports and returns its server and swarm port
+pub async fn create_test_node(
+    manager: &NodeManager,
+    node_name: &str,
+    run_on_startup: bool,
+) -> (u32, u32) {
+    let (server_port, swarm_port) = (free_port(), free_port());
+    create_node(
+        manager,
+        node_name.to_string(),
+        server_port,
+        swarm_port,
+        run_on_startup,
+        LaunchProfile::default(),
+    )
+    .await
+    .expect("create node");
+    (server_port, swarm_port)
+}
+
+// Collects the events `select` maps to a value, in the order they are published
+pub fn record_events<T, F>(manager: &NodeManager, select: F) -> Arc<Mutex<Vec<T>>>
+where
+    T: Send + 'static,
+    F: Fn(&ManagerEvent) -> Option<T> + Send + Sync + 'static,
+{
+    let events = Arc::new(Mutex::new(Vec::new()));
+    manager
+        .events
+        .subscribe({
+            let events = Arc::clone(&events);
+            move |event| {
+                if let Some(value) = select(event) {
+                    events.lock().unwrap().push(value);
+                }
+            }
+        })
+        .expect("subscribe to events");
+    events
+}
+
+// Node processes are matched by name across the whole system, so names must not
+// collide between tests running in parallel
+pub fn unique_node_name(prefix: &str) -> String {
+    format!("{}-{}", prefix, std::process::id())
+}
+
+pub fn free_port() -> u32 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port() as u32)
+        .expect("find a free port")
+}
+
+pub async fn wait_until<F, Fut>(timeout: Duration, mut condition: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if condition().await {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+// Wait for a running node to print the given text
+pub async fn wait_for_output(manager: &NodeManager, node_name: &str, text: &str) -> bool {
+    wait_until(Duration::from_secs(10), || async {
+        get_node_output(manager, node_name.to_string())
+            .map(|output| output.contains(text))
+            .unwrap_or(false)
+    })
+    .await
+}
+
+// The `code` tag the frontend receives for the error
+pub fn error_code(report: &eyre::Report) -> String {
+    let error = serde_json::to_value(ManagerError::from_report(report)).expect("serialize error");
+    error["code"].as_str().expect("error code").to_string()
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::{free_port, open_manager, record_events, unique_node_name, wait_until};
+use node_multiplatform_tauri::control::{load_or_create_token, serve};
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::manager::NodeManager;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const TOKEN: &str = "test-token";
+
+struct Client {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl Client {
+    async fn start(manager: &NodeManager) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/invoke", listener.local_addr().unwrap());
+        tokio::spawn(serve(manager.clone(), listener, TOKEN.to_string()));
+        Client {
+            http: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn send(&self, token: Option<&str>, body: String) -> reqwest::Response {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .body(body);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.unwrap()
+    }
+
+    async fn invoke(&self, command: &str, args: Value) -> Value {
+        let body = json!({ "command": command, "args": args }).to_string();
+        let response = self.send(Some(TOKEN), body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_str(&response.text().await.unwrap()).unwrap()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_requests_without_valid_token() {
+    let (_data_dir, manager) = open_manager();
+    let client = Client::start(&manager).await;
+    let body = json!({ "command": "fetch_nodes" }).to_string();
+
+    let response = client.send(None, body.clone()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    for token in ["wrong-token", "test-toke", "test-token-2"] {
+        let response = client.send(Some(token), body.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_unknown_commands() {
+    let (_data_dir, manager) = open_manager();
+    let client = Client::start(&manager).await;
+
+    let body = json!({ "command": "format_disk" }).to_string();
+    let response = client.send(Some(TOKEN), body).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn returns_typed_errors() {
+    let (_data_dir, manager) = open_manager();
+    let client = Client::start(&manager).await;
+
+    let result = client
+        .invoke("start_node", json!({ "nodeName": "missing" }))
+        .await;
+    assert_eq!(result["success"], false);
+    assert_eq!(result["error"]["code"], "NODE_NOT_FOUND");
+    assert_eq!(result["error"]["node_name"], "missing");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drives_node_lifecycle() {
+    let (_data_dir, manager) = open_manager();
+    let client = Client::start(&manager).await;
+    let node_name = unique_node_name("control-api");
+
+    let result = client
+        .invoke(
+            "initialize_node",
+            json!({
+                "nodeName": node_name,
+                "serverPort": free_port(),
+                "swarmPort": free_port(),
+                "runOnStartup": false,
+            }),
+        )
+        .await;
+    assert_eq!(result["success"], true, "{}", result);
+
+    let result = client.invoke("fetch_nodes", json!({})).await;
+    let nodes = result["data"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0]["name"], node_name.as_str());
+    assert_eq!(nodes[0]["is_running"], false);
+
+    let result = client
+        .invoke("start_node", json!({ "nodeName": node_name }))
+        .await;
+    assert_eq!(result["success"], true, "{}", result);
+
+    let output_contains = |text: &'static str| {
+        let client = &client;
+        let node_name = node_name.clone();
+        move || {
+            let node_name = node_name.clone();
+            async move {
+                let result = client
+                    .invoke("get_node_current_output", json!({ "nodeName": node_name }))
+                    .await;
+                result["data"].as_str().unwrap_or_default().contains(text)
+            }
+        }
+    };
+    assert!(wait_until(Duration::from_secs(10), output_contains("is running")).await);
+
+    let result = client
+        .invoke(
+            "send_input",
+            json!({ "nodeName": node_name, "input": "ping" }),
+        )
+        .await;
+    assert_eq!(result["success"], true, "{}", result);
+    assert!(wait_until(Duration::from_secs(10), output_contains("received: ping")).await);
+
+    let result = client.invoke("fetch_nodes", json!({})).await;
+    assert_eq!(result["data"][0]["is_running"], true);
+
+    let result = client
+        .invoke("stop_node", json!({ "nodeName": node_name }))
+        .await;
+    assert_eq!(result["success"], true, "{}", result);
+
+    let result = client.invoke("fetch_nodes", json!({})).await;
+    assert_eq!(result["data"][0]["is_running"], false);
+
+    let result = client
+        .invoke("get_node_log", json!({ "nodeName": node_name }))
+        .await;
+    assert!(result["data"].as_str().unwrap().contains("STDIN: ping"));
+
+    let result = client
+        .invoke("delete_node", json!({ "nodeName": node_name }))
+        .await;
+    assert_eq!(result["success"], true, "{}", result);
+
+    let result = client.invoke("fetch_nodes", json!({})).await;
+    assert_eq!(result["data"].as_array().unwrap().len(), 0);
+    let result = client.invoke("fetch_trash", json!({})).await;
+    assert_eq!(result["data"][0]["node_name"], node_name.as_str());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn saving_settings_toggles_run_app_on_startup() {
+    let (_data_dir, manager) = open_manager();
+    let toggles = record_events(&manager, |event| match event {
+        ManagerEvent::RunAppOnStartupChanged { enabled } => Some(*enabled),
+        _ => None,
+    });
+    let client = Client::start(&manager).await;
+
+    let mut settings = client.invoke("fetch_settings", json!({})).await["data"].clone();
+    let enabled = !settings["app"]["run_app_on_startup"].as_bool().unwrap();
+    settings["app"]["run_app_on_startup"] = json!(enabled);
+    for _ in 0..2 {
+        let result = client
+            .invoke("save_settings", json!({ "settings": settings }))
+            .await;
+        assert_eq!(result["success"], true);
+    }
+
+    // Saving unchanged settings leaves the login items alone
+    assert_eq!(*toggles.lock().unwrap(), vec![enabled]);
+}
+
+#[test]
+fn token_is_created_once_and_kept_private() {
+    let data_dir = tempfile::tempdir().unwrap();
+
+    let token = load_or_create_token(data_dir.path()).unwrap();
+    assert_eq!(token.len(), 64);
+    assert_eq!(load_or_create_token(data_dir.path()).unwrap(), token);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(data_dir.path().join("control_api.token")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+}
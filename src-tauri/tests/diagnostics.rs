@@ -0,0 +1,77 @@
+// Diagnostic bundles of nodes, with their secrets redacted
+mod common;
+
+use common::{create_test_node, open_manager, unique_node_name};
+use flate2::read::GzDecoder;
+use node_multiplatform_tauri::diagnostics::create_diagnostic_bundle;
+use node_multiplatform_tauri::types::{DiagnosticSummary, HealthStatus, PortKind};
+use node_multiplatform_tauri::utils::get_nodes_dir;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use tar::Archive;
+
+fn read_bundle(bundle_path: &Path) -> HashMap<String, String> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(bundle_path).unwrap()));
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().display().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            (path, content)
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundles_node_config_logs_and_summary() {
+    let (data_dir, manager) = open_manager();
+    let node_name = unique_node_name("diagnostics");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+    let config_path = get_nodes_dir(&manager).join(&node_name).join("config.toml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(
+        &config_path,
+        config.replace(
+            "[identity]\n",
+            "[identity]\nkeypair = \"23jhTbXRXh1RPMwzN2B7GNXZDiDtrkdm\"\n",
+        ),
+    )
+    .unwrap();
+
+    let bundle_path = create_diagnostic_bundle(&manager, vec![node_name.clone()], None).unwrap();
+    assert!(bundle_path.starts_with(data_dir.path().join("diagnostics")));
+    let files = read_bundle(&bundle_path);
+
+    let config = &files[&format!("nodes/{}/config.toml", node_name)];
+    assert!(config.contains("keypair = \"[REDACTED]\""));
+    assert!(!config.contains("23jhTbXRXh1RPMwzN2B7GNXZDiDtrkdm"));
+    assert!(files[&format!("nodes/{}/node.log", node_name)].contains("Initialized node"));
+    assert!(files.contains_key("settings.json"));
+
+    let summary: DiagnosticSummary = serde_json::from_str(&files["summary.json"]).unwrap();
+    assert_eq!(summary.meroctl_version, "meroctl 0.0.0-fake");
+    assert_eq!(summary.nodes.len(), 1);
+    let node = &summary.nodes[0];
+    assert_eq!(node.node_name, node_name);
+    assert!(!node.is_running);
+    assert_eq!(node.health, HealthStatus::Stopped);
+    assert_eq!(node.ports[0].kind, PortKind::Server);
+    assert_eq!(node.ports[0].port, server_port as u16);
+    assert!(!node.ports[0].in_use);
+    assert!(node.errors.is_empty());
+
+    // Unknown nodes fail the bundle without leaving a file behind
+    let destination = data_dir.path().join("unknown.tar.gz");
+    assert!(create_diagnostic_bundle(
+        &manager,
+        vec![node_name, "unknown".to_string()],
+        Some(destination.clone())
+    )
+    .is_err());
+    assert!(!destination.exists());
+}
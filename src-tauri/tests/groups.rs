@@ -0,0 +1,101 @@
+// Start order of node groups, resolved from the dependencies between their members
+use node_multiplatform_tauri::operations::groups::{
+    order_nodes, resolve_start_order, validate_group_structure,
+};
+use node_multiplatform_tauri::types::{GroupMember, NodeGroup};
+
+fn member(node_name: &str, depends_on: &[&str]) -> GroupMember {
+    GroupMember {
+        node_name: node_name.to_string(),
+        depends_on: depends_on.iter().map(|name| name.to_string()).collect(),
+        wait_until_ready: !depends_on.is_empty(),
+    }
+}
+
+fn group(name: &str, members: Vec<GroupMember>) -> NodeGroup {
+    NodeGroup {
+        name: name.to_string(),
+        members,
+    }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn starts_dependencies_first() {
+    let group = group(
+        "network",
+        vec![
+            member("app", &["relay", "db"]),
+            member("db", &[]),
+            member("relay", &["db"]),
+            member("monitor", &[]),
+        ],
+    );
+    assert_eq!(
+        resolve_start_order(&group).unwrap(),
+        names(&["db", "relay", "app", "monitor"])
+    );
+    validate_group_structure(&group).unwrap();
+}
+
+#[test]
+fn rejects_cycles_and_unknown_members() {
+    let cyclic = group(
+        "cyclic",
+        vec![
+            member("a", &["b"]),
+            member("b", &["c"]),
+            member("c", &["a"]),
+        ],
+    );
+    let error = resolve_start_order(&cyclic).unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "Dependency cycle detected in group cyclic: a -> b -> c -> a"
+    );
+    assert!(validate_group_structure(&cyclic).is_err());
+
+    let self_dependent = group("self", vec![member("a", &["a"])]);
+    assert!(resolve_start_order(&self_dependent).is_err());
+
+    let unknown = group("unknown", vec![member("a", &["missing"])]);
+    let error = resolve_start_order(&unknown).unwrap_err().to_string();
+    assert_eq!(error, "Node missing is not a member of group unknown");
+    let error = validate_group_structure(&unknown).unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "Node a depends on missing, which is not a member of group unknown"
+    );
+
+    let duplicate = group("duplicate", vec![member("a", &[]), member("a", &[])]);
+    assert!(validate_group_structure(&duplicate).is_err());
+}
+
+#[test]
+fn orders_group_members_before_other_nodes() {
+    let groups = vec![
+        group("first", vec![member("b", &["a"]), member("a", &[])]),
+        // Invalid groups are skipped
+        group("cyclic", vec![member("x", &["y"]), member("y", &["x"])]),
+        group("second", vec![member("c", &["a"]), member("a", &[])]),
+    ];
+    let ordered = order_nodes(&groups, &names(&["z", "c", "x", "y", "b", "a"]));
+    assert_eq!(
+        ordered,
+        vec![
+            ("a".to_string(), false),
+            ("b".to_string(), true),
+            ("c".to_string(), true),
+            ("x".to_string(), false),
+            ("y".to_string(), false),
+            ("z".to_string(), false),
+        ]
+    );
+
+    // Members that are not among the nodes are left out
+    let ordered = order_nodes(&groups, &names(&["b"]));
+    assert_eq!(ordered, vec![("b".to_string(), true)]);
+}
@@ -0,0 +1,108 @@
+// Health probing of nodes against a stub of the admin API and the fake meroctl
+mod common;
+
+use axum::{http::StatusCode, routing::get, Router};
+use common::{
+    create_test_node, free_port, open_manager, record_events, unique_node_name, wait_for_output,
+};
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::health::{
+    admin_api_health_url, classify_health, current_health, probe_admin_api, refresh_health,
+};
+use node_multiplatform_tauri::operations::{start_node, stop_node_process};
+use node_multiplatform_tauri::types::{HealthStatus, NodeEvent};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+async fn start_stub(status: StatusCode) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let router = Router::new().route("/admin-api/health", get(move || async move { status }));
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    port
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn probes_admin_api_health_endpoint() {
+    let port = start_stub(StatusCode::OK).await;
+    assert!(probe_admin_api(&admin_api_health_url(port)).await);
+
+    let port = start_stub(StatusCode::SERVICE_UNAVAILABLE).await;
+    assert!(!probe_admin_api(&admin_api_health_url(port)).await);
+
+    // Nothing listening
+    let port = free_port() as u16;
+    assert!(!probe_admin_api(&admin_api_health_url(port)).await);
+}
+
+#[test]
+fn classifies_health_from_reachability() {
+    let started = Duration::from_secs(1);
+    let running = Duration::from_secs(60);
+    assert_eq!(classify_health(true, true, started), HealthStatus::Healthy);
+    assert_eq!(classify_health(true, true, running), HealthStatus::Healthy);
+    // Nodes get a grace period to come up
+    assert_eq!(
+        classify_health(false, false, started),
+        HealthStatus::Starting
+    );
+    assert_eq!(
+        classify_health(true, false, started),
+        HealthStatus::Starting
+    );
+    assert_eq!(
+        classify_health(true, false, running),
+        HealthStatus::Degraded
+    );
+    assert_eq!(
+        classify_health(false, true, running),
+        HealthStatus::Degraded
+    );
+    assert_eq!(
+        classify_health(false, false, running),
+        HealthStatus::Unreachable
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn refreshes_health_of_running_nodes() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("health");
+    create_test_node(&manager, &node_name, false).await;
+    let events = record_events(&manager, |event| Some(event.clone()));
+
+    refresh_health(&manager).await.unwrap();
+    assert_eq!(
+        current_health(&manager, &node_name, false).unwrap(),
+        HealthStatus::Stopped
+    );
+
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    assert_eq!(
+        current_health(&manager, &node_name, true).unwrap(),
+        HealthStatus::Starting
+    );
+    // The fake meroctl answers on its server port and listens on its swarm port
+    refresh_health(&manager).await.unwrap();
+    assert_eq!(
+        current_health(&manager, &node_name, true).unwrap(),
+        HealthStatus::Healthy
+    );
+    stop_node_process(&manager, node_name.clone())
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        ManagerEvent::HealthChanged(change)
+            if change.node_name == node_name
+                && change.previous == HealthStatus::Starting
+                && change.status == HealthStatus::Healthy
+    )));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        ManagerEvent::Node(NodeEvent::Ready { node_name: ready }) if *ready == node_name
+    )));
+}
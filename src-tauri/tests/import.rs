@@ -0,0 +1,191 @@
+// Importing node directories created outside the application, copied or linked in place
+mod common;
+
+use common::{create_test_node, free_port, open_manager, unique_node_name};
+use node_multiplatform_tauri::logger::{get_log_file_path, read_log_file};
+use node_multiplatform_tauri::operations::import::import_node;
+use node_multiplatform_tauri::operations::{
+    create_node, delete_node, get_nodes, update_node_config,
+};
+use node_multiplatform_tauri::trash::{list_trash, restore_from_trash};
+use node_multiplatform_tauri::types::{ImportMode, LaunchProfile};
+use node_multiplatform_tauri::utils::{get_node_ports, get_nodes_dir};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+// A node home initialized by meroctl outside of the manager under test
+async fn external_node_home(prefix: &str) -> (TempDir, PathBuf, u32, u32) {
+    let (data_dir, manager) = open_manager();
+    let node_name = unique_node_name(prefix);
+    let (server_port, swarm_port) = create_test_node(&manager, &node_name, false).await;
+    let home = get_nodes_dir(&manager).join(&node_name);
+    fs::remove_file(home.join("node.log")).unwrap();
+    (data_dir, home, server_port, swarm_port)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn imports_copy_of_node_directory() {
+    let (_source_dir, home, server_port, swarm_port) = external_node_home("import-copy").await;
+    fs::create_dir(home.join("data")).unwrap();
+    fs::write(home.join("data/state.db"), "contexts").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("state.db", home.join("data/current.db")).unwrap();
+
+    let (_data_dir, manager) = open_manager();
+    let node_name =
+        import_node(&manager, home.clone(), None, ImportMode::Copy, None, None).unwrap();
+    assert_eq!(node_name, home.file_name().unwrap().to_str().unwrap());
+
+    let node_dir = get_nodes_dir(&manager).join(&node_name);
+    assert!(!node_dir.is_symlink());
+    assert_eq!(
+        fs::read_to_string(node_dir.join("data/state.db")).unwrap(),
+        "contexts"
+    );
+    // Links are copied as links rather than followed
+    #[cfg(unix)]
+    assert_eq!(
+        fs::read_link(node_dir.join("data/current.db")).unwrap(),
+        PathBuf::from("state.db")
+    );
+    let ports = get_node_ports(&node_name, &manager).unwrap();
+    assert_eq!(ports.server_port as u32, server_port);
+    assert_eq!(ports.swarm_port as u32, swarm_port);
+    assert!(get_nodes(&manager)
+        .unwrap()
+        .iter()
+        .any(|node| node.name == node_name));
+    assert!(!home.join("node.log").exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn imports_link_without_writing_into_node_directory() {
+    let (_source_dir, home, _, _) = external_node_home("import-link").await;
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("linked");
+    let (server_port, swarm_port) = (free_port(), free_port());
+    import_node(
+        &manager,
+        home.clone(),
+        Some(node_name.clone()),
+        ImportMode::Link,
+        Some(server_port),
+        Some(swarm_port),
+    )
+    .unwrap();
+
+    let nodes_dir = get_nodes_dir(&manager);
+    assert!(nodes_dir.join(&node_name).is_symlink());
+    // Overridden ports are written to the linked config
+    let ports = get_node_ports(&node_name, &manager).unwrap();
+    assert_eq!(ports.server_port as u32, server_port);
+    assert_eq!(ports.swarm_port as u32, swarm_port);
+
+    // The log is kept in the workspace home, next to the link
+    let log_path = get_log_file_path(&manager, &node_name);
+    assert!(log_path.starts_with(&nodes_dir));
+    assert!(log_path.exists());
+    assert!(!home.join("node.log").exists());
+    assert_eq!(
+        get_nodes(&manager)
+            .unwrap()
+            .iter()
+            .filter(|node| node.name.contains(&node_name))
+            .count(),
+        1
+    );
+
+    // The log follows the node when it is renamed, trashed and restored
+    let renamed = format!("{}-renamed", node_name);
+    update_node_config(
+        &manager,
+        node_name.clone(),
+        renamed.clone(),
+        server_port,
+        swarm_port,
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(!log_path.exists());
+    assert!(get_log_file_path(&manager, &renamed).exists());
+    assert!(read_log_file(&manager, &renamed).is_ok());
+
+    delete_node(&manager, renamed.clone()).await.unwrap();
+    assert!(!get_log_file_path(&manager, &renamed).exists());
+    let trashed = list_trash(&manager).unwrap();
+    restore_from_trash(&manager, trashed[0].id.clone(), None).unwrap();
+    assert!(get_log_file_path(&manager, &renamed).exists());
+    assert!(!home.join("node.log").exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_invalid_imports() {
+    let (_source_dir, home, server_port, _) = external_node_home("import-invalid").await;
+    let (_data_dir, manager) = open_manager();
+    let import = |node_name: Option<&str>, server_port: Option<u32>, swarm_port: Option<u32>| {
+        import_node(
+            &manager,
+            home.clone(),
+            node_name.map(str::to_string),
+            ImportMode::Copy,
+            server_port,
+            swarm_port,
+        )
+    };
+
+    for node_name in ["", " ", "../escape", "a/b", "a\\b", ".."] {
+        assert!(
+            import(Some(node_name), None, None).is_err(),
+            "{:?} should be rejected",
+            node_name
+        );
+    }
+    let port = free_port();
+    assert!(import(None, Some(port), Some(port)).is_err());
+
+    // Ports assigned to another node have to be overridden
+    let node_name = unique_node_name("import-existing");
+    create_node(
+        &manager,
+        node_name.clone(),
+        server_port,
+        free_port(),
+        false,
+        LaunchProfile::default(),
+    )
+    .await
+    .unwrap();
+    assert!(import(Some("imported"), None, None).is_err());
+    // Names of existing nodes are rejected
+    assert!(import(Some(&node_name), Some(free_port()), None).is_err());
+    import(Some("imported"), Some(free_port()), None).unwrap();
+
+    // Directories without a config and the manager's own nodes are rejected
+    let empty = tempfile::tempdir().unwrap();
+    assert!(import_node(
+        &manager,
+        empty.path().to_path_buf(),
+        None,
+        ImportMode::Copy,
+        None,
+        None
+    )
+    .is_err());
+    let own = get_nodes_dir(&manager).join(&node_name);
+    assert!(import_node(
+        &manager,
+        own,
+        Some("again".to_string()),
+        ImportMode::Link,
+        Some(free_port()),
+        Some(free_port())
+    )
+    .is_err());
+
+    let nodes_dir = get_nodes_dir(&manager);
+    assert!(!nodes_dir.join("escape").exists());
+    assert!(!nodes_dir.join("again").exists());
+    assert!(!nodes_dir.parent().unwrap().join("escape").exists());
+}
@@ -0,0 +1,172 @@
+// The JSON-RPC console against a stub endpoint listening on the node's server port
+mod common;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+use common::{create_test_node, error_code, open_manager, unique_node_name};
+use node_multiplatform_tauri::jsonrpc::{
+    clear_rpc_history, get_rpc_history, replay_rpc_request, send_rpc_request,
+};
+use node_multiplatform_tauri::types::{RpcRequest, RpcRequestKind};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+type Requests = Arc<Mutex<Vec<Value>>>;
+
+async fn stub_jsonrpc(
+    State(requests): State<Requests>,
+    Json(body): Json<Value>,
+) -> impl IntoResponse {
+    requests.lock().unwrap().push(body.clone());
+    let params = &body["params"];
+    let response = match params["method"].as_str() {
+        Some("get") => json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": { "output": { "value": params["argsJson"]["key"] } }
+        }),
+        Some("set") => json!({ "jsonrpc": "2.0", "id": body["id"], "result": { "output": null } }),
+        Some("crash") => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "internal error").into_response()
+        }
+        _ => json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "error": { "type": "FunctionCallError", "data": "method not found" }
+        }),
+    };
+    Json(response).into_response()
+}
+
+async fn start_stub(server_port: u32) -> Requests {
+    let requests = Requests::default();
+    let listener = TcpListener::bind(("127.0.0.1", server_port as u16))
+        .await
+        .unwrap();
+    let router = Router::new()
+        .route("/jsonrpc/dev", post(stub_jsonrpc))
+        .with_state(Arc::clone(&requests));
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    requests
+}
+
+fn request(kind: RpcRequestKind, method: &str, args: Value) -> RpcRequest {
+    RpcRequest {
+        kind,
+        context_id: "ctx1".to_string(),
+        method: method.to_string(),
+        args,
+        executor_public_key: "member1".to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_requests_to_node_context() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("jsonrpc");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+
+    let result = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Query, "get", json!({ "key": "a" })),
+    )
+    .await;
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_NOT_RUNNING");
+
+    let requests = start_stub(server_port).await;
+    let entry = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Query, "get", json!({ "key": "a" })),
+    )
+    .await
+    .unwrap();
+    let response = entry.response.expect("a response");
+    assert_eq!(response.output, Some(json!({ "value": "a" })));
+    assert!(response.error.is_none());
+    assert_eq!(
+        requests.lock().unwrap().last().unwrap(),
+        &json!({
+            "jsonrpc": "2.0",
+            "id": entry.id,
+            "method": "query",
+            "params": {
+                "contextId": "ctx1",
+                "method": "get",
+                "argsJson": { "key": "a" },
+                "executorPublicKey": "member1"
+            }
+        })
+    );
+
+    // Methods without arguments are called with an empty object
+    let entry = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Mutate, "set", Value::Null),
+    )
+    .await
+    .unwrap();
+    assert_eq!(entry.response.unwrap().output, None);
+    let body = requests.lock().unwrap().last().unwrap().clone();
+    assert_eq!(body["method"], "mutate");
+    assert_eq!(body["params"]["argsJson"], json!({}));
+
+    // Errors reported by the node are part of the response
+    let entry = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Query, "missing", json!({})),
+    )
+    .await
+    .unwrap();
+    let response = entry.response.unwrap();
+    assert!(response.output.is_none());
+    assert_eq!(response.error.unwrap()["type"], "FunctionCallError");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keeps_history_and_replays_requests() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("jsonrpc-history");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+    let requests = start_stub(server_port).await;
+
+    let first = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Query, "get", json!({ "key": "a" })),
+    )
+    .await
+    .unwrap();
+    let result = send_rpc_request(
+        &manager,
+        &node_name,
+        request(RpcRequestKind::Mutate, "crash", json!({})),
+    )
+    .await;
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("JSON-RPC request failed with 500"));
+
+    let history = get_rpc_history(&manager, &node_name).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].id, first.id);
+    assert!(history[1].response.is_none());
+    assert!(history[1].failure.is_some());
+
+    let replayed = replay_rpc_request(&manager, &node_name, first.id)
+        .await
+        .unwrap();
+    assert_ne!(replayed.id, first.id);
+    assert_eq!(replayed.request, first.request);
+    assert_eq!(requests.lock().unwrap().len(), 3);
+    assert_eq!(get_rpc_history(&manager, &node_name).unwrap().len(), 3);
+
+    assert!(replay_rpc_request(&manager, &node_name, 0).await.is_err());
+
+    clear_rpc_history(&manager, &node_name).unwrap();
+    assert!(get_rpc_history(&manager, &node_name).unwrap().is_empty());
+}
@@ -0,0 +1,179 @@
+// Validation of resource limits and the watchdog acting on soft limit violations
+mod common;
+
+use common::{create_test_node, open_manager, unique_node_name, wait_for_output};
+use node_multiplatform_tauri::limits::{check_soft_limits, validate_resource_limits};
+use node_multiplatform_tauri::logger::read_log_file;
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::operations::{start_node, stop_node_process};
+use node_multiplatform_tauri::store::modify_settings;
+use node_multiplatform_tauri::types::{
+    NodeMetricsSample, NodeSettings, ResourceLimits, WatchdogAction,
+};
+use node_multiplatform_tauri::utils::{get_nodes_dir, is_node_process_running};
+use std::collections::HashMap;
+use std::fs;
+
+fn record_memory(manager: &NodeManager, node_name: &str, memory_mb: u64) {
+    manager
+        .metrics
+        .lock()
+        .unwrap()
+        .entry(node_name.to_string())
+        .or_default()
+        .push_back(NodeMetricsSample {
+            timestamp: 0,
+            cpu_percent: 0.0,
+            memory_bytes: memory_mb * 1024 * 1024,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            data_dir_bytes: 0,
+            open_files: None,
+        });
+}
+
+fn set_soft_memory_limit(manager: &NodeManager, node_name: &str, action: WatchdogAction) {
+    modify_settings(manager, |settings| {
+        settings.workspace_mut()?.nodes.insert(
+            node_name.to_string(),
+            NodeSettings {
+                resource_limits: ResourceLimits {
+                    soft_memory_mb: Some(100),
+                    watchdog_action: action,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn validates_resource_limits() {
+    validate_resource_limits(&ResourceLimits::default()).unwrap();
+    validate_resource_limits(&ResourceLimits {
+        max_memory_mb: Some(1024),
+        soft_memory_mb: Some(512),
+        nice: Some(10),
+        max_open_files: Some(4096),
+        soft_cpu_percent: Some(80.0),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let invalid = [
+        ResourceLimits {
+            max_memory_mb: Some(0),
+            ..Default::default()
+        },
+        ResourceLimits {
+            soft_memory_mb: Some(0),
+            ..Default::default()
+        },
+        ResourceLimits {
+            max_memory_mb: Some(512),
+            soft_memory_mb: Some(1024),
+            ..Default::default()
+        },
+        ResourceLimits {
+            nice: Some(-1),
+            ..Default::default()
+        },
+        ResourceLimits {
+            nice: Some(20),
+            ..Default::default()
+        },
+        ResourceLimits {
+            max_open_files: Some(0),
+            ..Default::default()
+        },
+        ResourceLimits {
+            soft_cpu_percent: Some(0.0),
+            ..Default::default()
+        },
+    ];
+    for limits in invalid {
+        assert!(
+            validate_resource_limits(&limits).is_err(),
+            "{:?} should be rejected",
+            limits
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stops_nodes_that_keep_exceeding_soft_limits() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("watchdog");
+    create_test_node(&manager, &node_name, false).await;
+    set_soft_memory_limit(&manager, &node_name, WatchdogAction::Stop);
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+
+    let mut violations = HashMap::new();
+    record_memory(&manager, &node_name, 200);
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    assert_eq!(violations[&node_name], 2);
+
+    // The count starts over once the node is back under its limits
+    record_memory(&manager, &node_name, 50);
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    assert!(!violations.contains_key(&node_name));
+
+    record_memory(&manager, &node_name, 200);
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    assert!(is_node_process_running(&manager, &node_name).unwrap());
+    check_soft_limits(&manager, &mut violations).await.unwrap();
+    assert!(!is_node_process_running(&manager, &node_name).unwrap());
+    assert!(violations.is_empty());
+
+    let log = read_log_file(&manager, &node_name).unwrap();
+    assert!(log.contains(&format!(
+        "Watchdog stopping node '{}': memory usage 200 MB exceeds soft limit of 100 MB",
+        node_name
+    )));
+    let _ = stop_node_process(&manager, node_name).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restarts_nodes_and_keeps_checking_after_failures() {
+    let (_data_dir, manager) = open_manager();
+    let restarted = unique_node_name("watchdog-restart");
+    let broken = unique_node_name("watchdog-broken");
+    let stopped = unique_node_name("watchdog-stopped");
+    let nodes = [
+        (&restarted, WatchdogAction::Restart),
+        (&broken, WatchdogAction::Restart),
+        (&stopped, WatchdogAction::Stop),
+    ];
+    for (node_name, action) in nodes {
+        create_test_node(&manager, node_name, false).await;
+        set_soft_memory_limit(&manager, node_name, action);
+        start_node(&manager, node_name.clone()).await.unwrap();
+        assert!(wait_for_output(&manager, node_name, "is running").await);
+    }
+    // Without its config the node can't be started again
+    fs::remove_file(get_nodes_dir(&manager).join(&broken).join("config.toml")).unwrap();
+
+    let mut violations = HashMap::new();
+    for _ in 0..3 {
+        for (node_name, _) in nodes {
+            record_memory(&manager, node_name, 200);
+        }
+        check_soft_limits(&manager, &mut violations).await.unwrap();
+    }
+
+    // The restarted node is started again once its ports are released
+    assert!(wait_for_output(&manager, &restarted, "is running").await);
+    assert!(is_node_process_running(&manager, &restarted).unwrap());
+    assert!(!is_node_process_running(&manager, &broken).unwrap());
+    assert!(!is_node_process_running(&manager, &stopped).unwrap());
+    assert!(read_log_file(&manager, &restarted)
+        .unwrap()
+        .contains("Watchdog restarting node"));
+    stop_node_process(&manager, restarted).await.unwrap();
+}
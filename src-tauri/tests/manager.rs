@@ -0,0 +1,321 @@
+// The node manager runs without the desktop app: everything it needs comes from the
+// data directory and runtime it is opened with.
+mod common;
+
+use common::{
+    create_test_node, fake_runtime, open_manager, record_events, unique_node_name, wait_for_output,
+    wait_until,
+};
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::manager::{ManagerConfig, NodeManager};
+use node_multiplatform_tauri::operations::{
+    delete_node, get_node_output, get_nodes, start_node, stop_node_process, update_app_settings,
+};
+use node_multiplatform_tauri::store::{
+    get_run_node_on_startup, get_settings, remove_node_settings, rename_node_settings,
+    update_run_node_on_startup,
+};
+use node_multiplatform_tauri::trash::{list_trash, restore_from_trash};
+use node_multiplatform_tauri::utils::{
+    copy_and_remove, get_default_nodes_dir, get_nodes_dir, is_node_process_running,
+    kill_node_process,
+};
+use node_multiplatform_tauri::workspace::{save_workspace, switch_workspace};
+use std::fs;
+use std::time::Duration;
+
+fn node_names(manager: &NodeManager) -> Vec<String> {
+    get_nodes(manager)
+        .expect("list nodes")
+        .into_iter()
+        .map(|node| node.name)
+        .collect()
+}
+
+#[tokio::test]
+async fn reopens_nodes_and_settings_from_data_dir() {
+    let (data_dir, manager) = open_manager();
+    let node_name = unique_node_name("reopen");
+    create_test_node(&manager, &node_name, true).await;
+    drop(manager);
+
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime: fake_runtime(),
+    })
+    .unwrap();
+
+    assert_eq!(get_nodes_dir(&manager), data_dir.path().join("nodes"));
+    assert_eq!(node_names(&manager), vec![node_name.clone()]);
+    assert!(get_run_node_on_startup(&manager, &node_name).unwrap());
+    assert_eq!(get_node_output(&manager, node_name).unwrap(), "");
+}
+
+#[test]
+fn keeps_node_settings_per_name() {
+    let (_data_dir, manager) = open_manager();
+
+    assert!(!get_run_node_on_startup(&manager, "node1").unwrap());
+    update_run_node_on_startup(&manager, "node1", true).unwrap();
+    assert!(get_run_node_on_startup(&manager, "node1").unwrap());
+
+    rename_node_settings(&manager, "node1", "node2").unwrap();
+    assert!(!get_run_node_on_startup(&manager, "node1").unwrap());
+    assert!(get_run_node_on_startup(&manager, "node2").unwrap());
+
+    remove_node_settings(&manager, "node2").unwrap();
+    assert!(!get_run_node_on_startup(&manager, "node2").unwrap());
+}
+
+#[test]
+fn publishes_settings_changes() {
+    let (_data_dir, manager) = open_manager();
+    let events = record_events(&manager, |event| Some(event.clone()));
+
+    let mut settings = get_settings(&manager).unwrap();
+    settings.app.run_app_on_startup = true;
+    update_app_settings(&manager, settings).unwrap();
+
+    assert!(get_settings(&manager).unwrap().app.run_app_on_startup);
+    assert!(events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|event| matches!(event, ManagerEvent::SettingsChanged)));
+
+    // The active workspace is only changed through switch_workspace
+    let mut settings = get_settings(&manager).unwrap();
+    settings.app.active_workspace = "other".to_string();
+    assert!(update_app_settings(&manager, settings).is_err());
+}
+
+#[tokio::test]
+async fn scopes_nodes_to_active_workspace() {
+    let (data_dir, manager) = open_manager();
+    let events = record_events(&manager, |event| Some(event.clone()));
+    let default_node = unique_node_name("default-workspace");
+    let staging_node = unique_node_name("staging-workspace");
+    create_test_node(&manager, &default_node, false).await;
+
+    let staging_home = data_dir.path().join("staging");
+    save_workspace(&manager, "staging".to_string(), Some(staging_home.clone())).unwrap();
+    switch_workspace(&manager, "staging".to_string()).unwrap();
+    assert_eq!(get_nodes_dir(&manager), staging_home);
+    assert!(node_names(&manager).is_empty());
+    assert!(events.lock().unwrap().iter().any(
+        |event| matches!(event, ManagerEvent::WorkspaceChanged { name } if name == "staging")
+    ));
+
+    create_test_node(&manager, &staging_node, false).await;
+    assert!(staging_home
+        .join(&staging_node)
+        .join("config.toml")
+        .exists());
+    assert_eq!(node_names(&manager), vec![staging_node]);
+
+    switch_workspace(&manager, "default".to_string()).unwrap();
+    assert_eq!(get_nodes_dir(&manager), get_default_nodes_dir(&manager));
+    assert_eq!(node_names(&manager), vec![default_node]);
+}
+
+#[test]
+fn rejects_workspaces_sharing_a_home() {
+    let (data_dir, manager) = open_manager();
+    // The default workspace has no home of its own and uses the default nodes directory
+    assert!(save_workspace(
+        &manager,
+        "explicit".to_string(),
+        Some(get_default_nodes_dir(&manager))
+    )
+    .is_err());
+
+    let home = data_dir.path().join("shared");
+    save_workspace(&manager, "first".to_string(), Some(home.clone())).unwrap();
+    assert!(save_workspace(&manager, "second".to_string(), Some(home)).is_err());
+    assert!(save_workspace(&manager, "second".to_string(), None).is_err());
+    let workspaces = get_settings(&manager).unwrap().workspaces;
+    assert!(!workspaces.contains_key("explicit"));
+    assert!(!workspaces.contains_key("second"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restores_trashed_node_into_its_workspace() {
+    let (data_dir, manager) = open_manager();
+    let node_name = unique_node_name("trashed-workspace");
+    create_test_node(&manager, &node_name, true).await;
+    delete_node(&manager, node_name.clone()).await.unwrap();
+
+    let staging_home = data_dir.path().join("staging");
+    save_workspace(&manager, "staging".to_string(), Some(staging_home.clone())).unwrap();
+    switch_workspace(&manager, "staging".to_string()).unwrap();
+    let trashed = list_trash(&manager).unwrap();
+    assert_eq!(trashed[0].workspace, "default");
+
+    restore_from_trash(&manager, trashed[0].id.clone(), None).unwrap();
+    assert!(get_default_nodes_dir(&manager)
+        .join(&node_name)
+        .join("config.toml")
+        .exists());
+    assert!(!staging_home.join(&node_name).exists());
+    assert!(node_names(&manager).is_empty());
+    let settings = get_settings(&manager).unwrap();
+    assert!(settings.workspaces["default"].nodes[&node_name].run_on_startup);
+    assert!(!settings.workspaces["staging"]
+        .nodes
+        .contains_key(&node_name));
+
+    switch_workspace(&manager, "default".to_string()).unwrap();
+    assert_eq!(node_names(&manager), vec![node_name]);
+}
+
+// Node names are only unique within a workspace, so processes are told apart by the
+// workspace home as well as by their exact name
+#[tokio::test(flavor = "multi_thread")]
+async fn tells_apart_nodes_with_the_same_name_in_other_workspaces() {
+    let (_data_dir, manager) = open_manager();
+    let (other_data_dir, other) = open_manager();
+    let node_name = unique_node_name("same-name");
+    let prefixed = format!("{}2", node_name);
+    save_workspace(
+        &other,
+        "staging".to_string(),
+        Some(other_data_dir.path().join("staging")),
+    )
+    .unwrap();
+    switch_workspace(&other, "staging".to_string()).unwrap();
+    create_test_node(&manager, &node_name, false).await;
+    create_test_node(&manager, &prefixed, false).await;
+    create_test_node(&other, &node_name, false).await;
+
+    start_node(&other, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&other, &node_name, "is running").await);
+    assert!(!is_node_process_running(&manager, &node_name).unwrap());
+
+    start_node(&manager, node_name.clone()).await.unwrap();
+    start_node(&manager, prefixed.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    assert!(wait_for_output(&manager, &prefixed, "is running").await);
+
+    // Killing a node without a handle to its process leaves the others running
+    kill_node_process(&manager, &node_name).unwrap();
+    assert!(
+        wait_until(Duration::from_secs(10), || async {
+            !is_node_process_running(&manager, &node_name).unwrap()
+        })
+        .await
+    );
+    assert!(is_node_process_running(&manager, &prefixed).unwrap());
+    assert!(is_node_process_running(&other, &node_name).unwrap());
+
+    stop_node_process(&manager, prefixed).await.unwrap();
+    stop_node_process(&other, node_name.clone()).await.unwrap();
+    assert!(!is_node_process_running(&other, &node_name).unwrap());
+}
+
+// Moves between filesystems, e.g. into the trash from a workspace home on another
+// drive, fall back to copying and removing the original
+#[test]
+fn copies_and_removes_moved_directories() {
+    let source_dir = tempfile::tempdir().unwrap();
+    let destination_dir = tempfile::tempdir().unwrap();
+    let source = source_dir.path().join("node");
+    fs::create_dir_all(source.join("data")).unwrap();
+    fs::write(source.join("config.toml"), "[server]").unwrap();
+    fs::write(source.join("data/state.db"), "contexts").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("data/state.db", source.join("state.db")).unwrap();
+
+    let destination = destination_dir.path().join("node");
+    copy_and_remove(&source, &destination).unwrap();
+    assert!(!source.exists());
+    assert_eq!(
+        fs::read_to_string(destination.join("data/state.db")).unwrap(),
+        "contexts"
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        fs::read_to_string(destination.join("state.db")).unwrap(),
+        "contexts"
+    );
+
+    let log = destination_dir.path().join("node.log");
+    fs::write(&log, "started").unwrap();
+    copy_and_remove(&log, &source_dir.path().join("node.log")).unwrap();
+    assert!(!log.exists());
+    assert_eq!(
+        fs::read_to_string(source_dir.path().join("node.log")).unwrap(),
+        "started"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test(flavor = "multi_thread")]
+async fn starts_nodes_marked_to_run_on_startup() {
+    use node_multiplatform_tauri::operations::{start_nodes_on_startup, stop_all_nodes};
+
+    let (_data_dir, manager) = open_manager();
+    let startup_node = unique_node_name("on-startup");
+    let manual_node = unique_node_name("manual");
+    create_test_node(&manager, &startup_node, true).await;
+    create_test_node(&manager, &manual_node, false).await;
+
+    start_nodes_on_startup(&manager).await.unwrap();
+    assert!(wait_for_output(&manager, &startup_node, "is running").await);
+
+    let running = |node_name: &str| {
+        get_nodes(&manager)
+            .unwrap()
+            .into_iter()
+            .any(|node| node.name == node_name && node.is_running)
+    };
+    assert!(running(&startup_node));
+    assert!(!running(&manual_node));
+
+    stop_all_nodes(&manager).await.unwrap();
+    assert!(!running(&startup_node));
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test(flavor = "multi_thread")]
+async fn starts_remaining_nodes_when_one_fails_on_startup() {
+    use node_multiplatform_tauri::operations::groups::save_group;
+    use node_multiplatform_tauri::operations::{start_nodes_on_startup, stop_all_nodes};
+    use node_multiplatform_tauri::types::{GroupMember, NodeGroup};
+
+    let (_data_dir, manager) = open_manager();
+    let broken = unique_node_name("a-broken");
+    let dependent = unique_node_name("b-dependent");
+    let independent = unique_node_name("c-independent");
+    for node_name in [&broken, &dependent, &independent] {
+        create_test_node(&manager, node_name, true).await;
+    }
+    save_group(
+        &manager,
+        NodeGroup {
+            name: "network".to_string(),
+            members: vec![
+                GroupMember {
+                    node_name: broken.clone(),
+                    depends_on: vec![],
+                    wait_until_ready: false,
+                },
+                GroupMember {
+                    node_name: dependent.clone(),
+                    depends_on: vec![broken.clone()],
+                    wait_until_ready: false,
+                },
+            ],
+        },
+    )
+    .unwrap();
+    // Without its config the node can't be started
+    fs::remove_file(get_nodes_dir(&manager).join(&broken).join("config.toml")).unwrap();
+
+    start_nodes_on_startup(&manager).await.unwrap();
+    assert!(wait_for_output(&manager, &independent, "is running").await);
+    assert!(is_node_process_running(&manager, &independent).unwrap());
+    assert!(!is_node_process_running(&manager, &broken).unwrap());
+    assert!(!is_node_process_running(&manager, &dependent).unwrap());
+
+    stop_all_nodes(&manager).await.unwrap();
+}
@@ -0,0 +1,104 @@
+// The per-node metrics ring buffer and its cleanup when nodes go away
+mod common;
+
+use common::{create_test_node, free_port, open_manager, unique_node_name, wait_for_output};
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::metrics::{
+    get_node_metrics, latest_metrics_summaries, push_sample, MAX_SAMPLES,
+};
+use node_multiplatform_tauri::operations::{
+    delete_node, start_node, stop_node_process, update_node_config,
+};
+use node_multiplatform_tauri::types::NodeMetricsSample;
+
+fn record_sample(manager: &NodeManager, node_name: &str, timestamp: i64) {
+    push_sample(
+        manager,
+        node_name,
+        NodeMetricsSample {
+            timestamp,
+            cpu_percent: 1.5,
+            memory_bytes: 64 * 1024 * 1024,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            data_dir_bytes: 0,
+            open_files: None,
+        },
+    )
+    .unwrap();
+}
+
+fn timestamps(manager: &NodeManager, node_name: &str) -> Vec<i64> {
+    get_node_metrics(manager, node_name)
+        .unwrap()
+        .iter()
+        .map(|sample| sample.timestamp)
+        .collect()
+}
+
+#[test]
+fn keeps_the_latest_samples_per_node() {
+    let (_data_dir, manager) = open_manager();
+    let extra = 5;
+    for timestamp in 0..(MAX_SAMPLES + extra) as i64 {
+        record_sample(&manager, "busy", timestamp);
+    }
+    record_sample(&manager, "quiet", 42);
+
+    let expected: Vec<i64> = (extra as i64..(MAX_SAMPLES + extra) as i64).collect();
+    assert_eq!(timestamps(&manager, "busy"), expected);
+    assert_eq!(timestamps(&manager, "quiet"), vec![42]);
+    assert!(get_node_metrics(&manager, "missing").unwrap().is_empty());
+
+    let summaries = latest_metrics_summaries(&manager).unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries["quiet"], "CPU 1.5% · RAM 64.0 MB");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drops_samples_when_a_node_stops() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("metrics-stop");
+    create_test_node(&manager, &node_name, false).await;
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+
+    record_sample(&manager, &node_name, 1);
+    stop_node_process(&manager, node_name.clone())
+        .await
+        .unwrap();
+
+    assert!(get_node_metrics(&manager, &node_name).unwrap().is_empty());
+    assert!(latest_metrics_summaries(&manager).unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_samples_on_rename_and_drops_them_on_delete() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("metrics-rename");
+    let new_name = unique_node_name("metrics-renamed");
+    create_test_node(&manager, &node_name, false).await;
+    record_sample(&manager, &node_name, 1);
+    // Left over from an earlier node that had the new name
+    record_sample(&manager, &new_name, 2);
+
+    update_node_config(
+        &manager,
+        node_name.clone(),
+        new_name.clone(),
+        free_port(),
+        free_port(),
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(get_node_metrics(&manager, &node_name).unwrap().is_empty());
+    assert_eq!(timestamps(&manager, &new_name), vec![1]);
+
+    delete_node(&manager, new_name.clone()).await.unwrap();
+    assert!(get_node_metrics(&manager, &new_name).unwrap().is_empty());
+
+    // A new node with the same name starts without history
+    create_test_node(&manager, &new_name, false).await;
+    assert!(get_node_metrics(&manager, &new_name).unwrap().is_empty());
+}
@@ -0,0 +1,181 @@
+// Notifications raised by manager events, filtered by the settings and rate limited
+mod common;
+
+use common::{create_test_node, open_manager, unique_node_name};
+use node_multiplatform_tauri::error::ManagerError;
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::notifications::{
+    notification_for_event, subscribe_notifications, Notification,
+};
+use node_multiplatform_tauri::operations::start_node;
+use node_multiplatform_tauri::store::modify_settings;
+use node_multiplatform_tauri::types::{
+    HealthChangedEvent, HealthStatus, NodeEvent, NodeSettings, NotificationKind,
+};
+use std::sync::{Arc, Mutex};
+
+fn exited(node_name: &str, exit_code: Option<i32>) -> ManagerEvent {
+    ManagerEvent::Node(NodeEvent::Exited {
+        node_name: node_name.to_string(),
+        exit_code,
+    })
+}
+
+fn health_changed(previous: HealthStatus, status: HealthStatus) -> ManagerEvent {
+    ManagerEvent::HealthChanged(HealthChangedEvent {
+        node_name: "node1".to_string(),
+        previous,
+        status,
+    })
+}
+
+fn record_notifications(manager: &NodeManager) -> Arc<Mutex<Vec<Notification>>> {
+    let notifications = Arc::new(Mutex::new(Vec::new()));
+    subscribe_notifications(manager, {
+        let notifications = Arc::clone(&notifications);
+        move |notification| notifications.lock().unwrap().push(notification.clone())
+    })
+    .unwrap();
+    notifications
+}
+
+#[test]
+fn raises_notifications_for_failures() {
+    let notification = notification_for_event(&exited("node1", Some(3))).unwrap();
+    assert_eq!(notification.kind, NotificationKind::NodeExited);
+    assert_eq!(notification.title, "Node node1 crashed");
+    assert_eq!(
+        notification.body,
+        "The node exited unexpectedly with code 3"
+    );
+
+    let notification = notification_for_event(&ManagerEvent::Node(NodeEvent::StartFailed {
+        node_name: "node1".to_string(),
+        error: ManagerError::PortInUse { port: 2428 },
+    }))
+    .unwrap();
+    assert_eq!(notification.kind, NotificationKind::PortConflict);
+    assert_eq!(notification.body, "Port 2428 is already in use");
+
+    let notification = notification_for_event(&health_changed(
+        HealthStatus::Healthy,
+        HealthStatus::Unreachable,
+    ))
+    .unwrap();
+    assert_eq!(notification.kind, NotificationKind::Unhealthy);
+
+    // Other start failures, recoveries and stopped nodes are not notified
+    assert!(
+        notification_for_event(&ManagerEvent::Node(NodeEvent::StartFailed {
+            node_name: "node1".to_string(),
+            error: ManagerError::NodeRunning {
+                node_name: "node1".to_string()
+            },
+        }))
+        .is_none()
+    );
+    assert!(notification_for_event(&health_changed(
+        HealthStatus::Degraded,
+        HealthStatus::Unreachable
+    ))
+    .is_none());
+    assert!(notification_for_event(&health_changed(
+        HealthStatus::Starting,
+        HealthStatus::Healthy
+    ))
+    .is_none());
+    assert!(
+        notification_for_event(&ManagerEvent::Node(NodeEvent::Stopped {
+            node_name: "node1".to_string()
+        }))
+        .is_none()
+    );
+}
+
+#[test]
+fn rate_limits_notifications_per_node_and_kind() {
+    let (_data_dir, manager) = open_manager();
+    let notifications = record_notifications(&manager);
+
+    manager.events.publish(exited("node1", Some(1))).unwrap();
+    manager.events.publish(exited("node1", Some(1))).unwrap();
+    manager.events.publish(exited("node2", Some(1))).unwrap();
+    manager
+        .events
+        .publish(health_changed(
+            HealthStatus::Healthy,
+            HealthStatus::Degraded,
+        ))
+        .unwrap();
+
+    let notifications = notifications.lock().unwrap();
+    let sent: Vec<_> = notifications
+        .iter()
+        .map(|notification| (notification.node_name.as_str(), notification.kind))
+        .collect();
+    assert_eq!(
+        sent,
+        vec![
+            ("node1", NotificationKind::NodeExited),
+            ("node2", NotificationKind::NodeExited),
+            ("node1", NotificationKind::Unhealthy),
+        ]
+    );
+}
+
+#[test]
+fn follows_notification_settings() {
+    let (_data_dir, manager) = open_manager();
+    let notifications = record_notifications(&manager);
+    modify_settings(&manager, |settings| {
+        settings.app.notifications.min_interval_secs = 0;
+        settings.app.notifications.kinds = vec![NotificationKind::NodeExited];
+        settings.workspace_mut()?.nodes.insert(
+            "node2".to_string(),
+            NodeSettings {
+                notifications: Some(vec![NotificationKind::Unhealthy]),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    })
+    .unwrap();
+
+    manager.events.publish(exited("node1", Some(1))).unwrap();
+    manager.events.publish(exited("node1", Some(1))).unwrap();
+    // node1 uses the app's kinds, node2 its own
+    manager
+        .events
+        .publish(health_changed(
+            HealthStatus::Healthy,
+            HealthStatus::Degraded,
+        ))
+        .unwrap();
+    manager.events.publish(exited("node2", Some(1))).unwrap();
+    assert_eq!(notifications.lock().unwrap().len(), 2);
+
+    modify_settings(&manager, |settings| {
+        settings.app.notifications.enabled = false;
+        Ok(())
+    })
+    .unwrap();
+    manager.events.publish(exited("node1", Some(1))).unwrap();
+    assert_eq!(notifications.lock().unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn notifies_port_conflict_on_start() {
+    let (_data_dir, manager) = open_manager();
+    let notifications = record_notifications(&manager);
+    let node_name = unique_node_name("port-conflict");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+
+    let _listener = std::net::TcpListener::bind(("127.0.0.1", server_port as u16)).unwrap();
+    assert!(start_node(&manager, node_name.clone()).await.is_err());
+
+    let notifications = notifications.lock().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].node_name, node_name);
+    assert_eq!(notifications[0].kind, NotificationKind::PortConflict);
+}
@@ -0,0 +1,258 @@
+// End to end tests of the node operations against the fake meroctl. Node processes
+// are looked up by their command line the way they are on Linux.
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::{
+    create_test_node, error_code, fake_runtime, free_port, open_manager, open_manager_with,
+    record_events, unique_node_name, wait_for_output, wait_until,
+};
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::logger::read_log_file;
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::operations::{
+    create_node, delete_node, get_node_output, get_nodes, send_input_to_node, start_node,
+    stop_node_process, update_node_config,
+};
+use node_multiplatform_tauri::trash::list_trash;
+use node_multiplatform_tauri::types::{LaunchProfile, NodeEvent, NodeInfo};
+use node_multiplatform_tauri::utils::get_nodes_dir;
+use std::sync::Mutex;
+use std::time::Duration;
+
+fn find_node(manager: &NodeManager, node_name: &str) -> Option<NodeInfo> {
+    get_nodes(manager)
+        .expect("list nodes")
+        .into_iter()
+        .find(|node| node.name == node_name)
+}
+
+async fn wait_for_exit(events: &Mutex<Vec<NodeEvent>>, node_name: &str, code: i32) -> bool {
+    wait_until(Duration::from_secs(10), || async {
+        events.lock().unwrap().iter().any(|event| match event {
+            NodeEvent::Exited {
+                node_name: name,
+                exit_code,
+            } => name == node_name && *exit_code == Some(code),
+            _ => false,
+        })
+    })
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn creates_node_with_config() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("create");
+
+    let (server_port, swarm_port) = create_test_node(&manager, &node_name, false).await;
+
+    assert!(get_nodes_dir(&manager)
+        .join(&node_name)
+        .join("config.toml")
+        .exists());
+    let node = find_node(&manager, &node_name).expect("node is listed");
+    assert_eq!(node.node_ports.server_port as u32, server_port);
+    assert_eq!(node.node_ports.swarm_port as u32, swarm_port);
+    assert!(!node.is_running);
+
+    let peer_id = node
+        .peer_info
+        .peer_id
+        .expect("peer id is read from the config");
+    assert!(peer_id.starts_with("12D3KooW"));
+    assert_eq!(
+        node.peer_info.multiaddrs,
+        vec![
+            format!("/ip4/127.0.0.1/tcp/{}/p2p/{}", swarm_port, peer_id),
+            format!("/ip4/127.0.0.1/udp/{}/quic-v1/p2p/{}", swarm_port, peer_id),
+        ]
+    );
+    assert_eq!(
+        node.peer_info.dashboard_url,
+        format!("http://localhost:{}/admin-dashboard", server_port)
+    );
+    assert_eq!(
+        node.peer_info.jsonrpc_url,
+        format!("http://localhost:{}/jsonrpc", server_port)
+    );
+
+    let result = create_node(
+        &manager,
+        node_name.clone(),
+        free_port(),
+        free_port(),
+        false,
+        LaunchProfile::default(),
+    )
+    .await;
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_ALREADY_EXISTS");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_failed_init() {
+    let (_data_dir, manager) =
+        open_manager_with(fake_runtime().with_env("FAKE_MEROCTL_INIT_EXIT_CODE", "1"));
+    let node_name = unique_node_name("failed-init");
+
+    let result = create_node(
+        &manager,
+        node_name.clone(),
+        free_port(),
+        free_port(),
+        false,
+        LaunchProfile::default(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(find_node(&manager, &node_name).is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn runs_node_and_captures_output() {
+    let (_data_dir, manager) = open_manager_with(fake_runtime().with_env(
+        "FAKE_MEROCTL_OUTPUT",
+        "\x1b[32mpeer id: 12D3KooWFake\x1b[0m",
+    ));
+    let node_name = unique_node_name("output");
+    create_test_node(&manager, &node_name, false).await;
+
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    assert!(find_node(&manager, &node_name).unwrap().is_running);
+
+    // Colors are stripped from the captured output
+    let output = get_node_output(&manager, node_name.clone()).unwrap();
+    assert!(output.contains("peer id: 12D3KooWFake"), "{}", output);
+    assert!(!output.contains('\x1b'));
+
+    send_input_to_node(node_name.clone(), "hello".to_string(), &manager).unwrap();
+    assert!(wait_for_output(&manager, &node_name, "received: hello").await);
+    let output = get_node_output(&manager, node_name.clone()).unwrap();
+    assert!(output.contains("> hello"));
+
+    let result = start_node(&manager, node_name.clone()).await;
+    assert!(result.is_err());
+
+    stop_node_process(&manager, node_name.clone())
+        .await
+        .unwrap();
+    assert!(!find_node(&manager, &node_name).unwrap().is_running);
+    assert_eq!(get_node_output(&manager, node_name.clone()).unwrap(), "");
+
+    let log = read_log_file(&manager, &node_name).unwrap();
+    assert!(log.contains("Node ") && log.contains(" is running"));
+    assert!(log.contains("STDIN: hello"));
+    assert!(log.contains("has been stopped successfully"));
+
+    let result = stop_node_process(&manager, node_name.clone()).await;
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_NOT_RUNNING");
+    let result = send_input_to_node(node_name.clone(), "hello".to_string(), &manager);
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_NOT_RUNNING");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_node_exit() {
+    let (_data_dir, manager) = open_manager_with(
+        fake_runtime()
+            .with_env("FAKE_MEROCTL_EXIT_CODE", "3")
+            .with_env("FAKE_MEROCTL_ERROR_OUTPUT", "fatal: out of disk space"),
+    );
+    let events = record_events(&manager, |event| match event {
+        ManagerEvent::Node(event) => Some(event.clone()),
+        _ => None,
+    });
+    let node_name = unique_node_name("exit");
+    create_test_node(&manager, &node_name, false).await;
+
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    send_input_to_node(node_name.clone(), "exit".to_string(), &manager).unwrap();
+
+    assert!(wait_for_exit(&events, &node_name, 3).await);
+
+    // stderr is captured along with stdout
+    let output = get_node_output(&manager, node_name.clone()).unwrap();
+    assert!(output.contains("fatal: out of disk space"), "{}", output);
+    assert!(!find_node(&manager, &node_name).unwrap().is_running);
+
+    // The node can be started again after it exited
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    send_input_to_node(node_name.clone(), "exit 0".to_string(), &manager).unwrap();
+    assert!(wait_for_exit(&events, &node_name, 0).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn renames_node() {
+    let (_data_dir, manager) = open_manager();
+    let events = record_events(&manager, |event| match event {
+        ManagerEvent::Node(event) => Some(event.clone()),
+        _ => None,
+    });
+    let node_name = unique_node_name("rename");
+    let new_name = unique_node_name("renamed");
+    create_test_node(&manager, &node_name, false).await;
+
+    let (server_port, swarm_port) = (free_port(), free_port());
+    update_node_config(
+        &manager,
+        node_name.clone(),
+        new_name.clone(),
+        server_port,
+        swarm_port,
+        true,
+    )
+    .await
+    .unwrap();
+
+    assert!(find_node(&manager, &node_name).is_none());
+    let node = find_node(&manager, &new_name).expect("renamed node is listed");
+    assert_eq!(node.node_ports.server_port as u32, server_port);
+    assert_eq!(node.node_ports.swarm_port as u32, swarm_port);
+    assert!(node.run_on_startup);
+    assert!(events.lock().unwrap().iter().any(|event| matches!(
+        event,
+        NodeEvent::Renamed { previous_name, node_name: name }
+            if *previous_name == node_name && *name == new_name
+    )));
+
+    // The renamed node runs on its new ports
+    start_node(&manager, new_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &new_name, "is running").await);
+    let output = get_node_output(&manager, new_name.clone()).unwrap();
+    assert!(output.contains(&format!("Node {} is running", new_name)));
+    assert!(std::net::TcpListener::bind(("127.0.0.1", server_port as u16)).is_err());
+    stop_node_process(&manager, new_name.clone()).await.unwrap();
+
+    let result = update_node_config(&manager, node_name.clone(), new_name, 0, 0, false).await;
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_NOT_FOUND");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deletes_node_to_trash() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("delete");
+    create_test_node(&manager, &node_name, false).await;
+
+    start_node(&manager, node_name.clone()).await.unwrap();
+    assert!(wait_for_output(&manager, &node_name, "is running").await);
+    let result = delete_node(&manager, node_name.clone()).await;
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_RUNNING");
+    stop_node_process(&manager, node_name.clone())
+        .await
+        .unwrap();
+
+    delete_node(&manager, node_name.clone()).await.unwrap();
+
+    assert!(find_node(&manager, &node_name).is_none());
+    assert!(!get_nodes_dir(&manager).join(&node_name).exists());
+    assert!(list_trash(&manager)
+        .unwrap()
+        .iter()
+        .any(|entry| entry.node_name == node_name));
+    let result = get_node_output(&manager, node_name.clone());
+    assert_eq!(error_code(&result.unwrap_err()), "NODE_NOT_FOUND");
+}
@@ -0,0 +1,164 @@
+// Migration of settings files written by older versions and reloading of the store
+mod common;
+
+use common::{fake_runtime, open_manager};
+use node_multiplatform_tauri::manager::{ManagerConfig, NodeManager};
+use node_multiplatform_tauri::store::{get_settings, modify_settings, SETTINGS_VERSION};
+use node_multiplatform_tauri::types::Settings;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const STORE_FILE: &str = "node_manager.dat";
+
+fn open_fixture(fixture: &str) -> (TempDir, NodeManager) {
+    let data_dir = tempfile::tempdir().unwrap();
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(fixture);
+    fs::copy(fixture, data_dir.path().join(STORE_FILE)).unwrap();
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime: fake_runtime(),
+    })
+    .unwrap();
+    (data_dir, manager)
+}
+
+fn read_store(data_dir: &TempDir) -> Value {
+    serde_json::from_slice(&fs::read(data_dir.path().join(STORE_FILE)).unwrap()).unwrap()
+}
+
+// Both fixtures hold the same node settings and group, in their own layout
+fn assert_migrated(settings: &Settings) {
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert!(!settings.app.run_app_on_startup);
+    assert_eq!(settings.app.active_workspace, "default");
+    assert_eq!(settings.workspaces.len(), 1);
+
+    let workspace = settings.workspace().unwrap();
+    assert_eq!(workspace.home, None);
+    assert_eq!(workspace.node_groups.len(), 1);
+    assert_eq!(workspace.node_groups[0].name, "network");
+    assert_eq!(workspace.node_groups[0].members[0].node_name, "node1");
+
+    let node1 = &workspace.nodes["node1"];
+    assert!(node1.run_on_startup);
+    assert_eq!(node1.resource_limits.max_memory_mb, Some(2048));
+    assert_eq!(node1.resource_limits.nice, Some(5));
+    let node2 = &workspace.nodes["node2"];
+    assert!(!node2.run_on_startup);
+    assert_eq!(node2.launch_profile.env["RUST_LOG"], "debug");
+    assert_eq!(node2.launch_profile.run_args, vec!["--verbose".to_string()]);
+}
+
+#[test]
+fn migrates_legacy_flat_keys() {
+    let (data_dir, manager) = open_fixture("legacy_store.json");
+    let settings = get_settings(&manager).unwrap();
+    assert_migrated(&settings);
+    let members = &settings.workspace().unwrap().node_groups[0].members;
+    assert_eq!(members[1].depends_on, vec!["node1".to_string()]);
+    assert!(members[1].wait_until_ready);
+
+    // The legacy keys are replaced by the settings, unrelated keys are kept
+    let store = read_store(&data_dir);
+    let keys: Vec<&String> = store.as_object().unwrap().keys().collect();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(store["theme"], "dark");
+    assert_eq!(store["settings"]["version"], SETTINGS_VERSION);
+}
+
+#[test]
+fn migrates_v1_settings_into_default_workspace() {
+    let (data_dir, manager) = open_fixture("v1_store.json");
+    let settings = get_settings(&manager).unwrap();
+    assert_migrated(&settings);
+    assert_eq!(settings.app.trash_retention_days, 7);
+
+    let store = read_store(&data_dir);
+    assert_eq!(store["settings"]["version"], SETTINGS_VERSION);
+    assert!(store["settings"].get("nodes").is_none());
+    assert!(store["settings"]["app"].get("node_groups").is_none());
+
+    // Migrated settings are not migrated again when the store is reopened
+    drop(manager);
+    let manager = NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime: fake_runtime(),
+    })
+    .unwrap();
+    assert_migrated(&get_settings(&manager).unwrap());
+}
+
+#[test]
+fn rejects_settings_newer_than_supported() {
+    let data_dir = tempfile::tempdir().unwrap();
+    fs::write(
+        data_dir.path().join(STORE_FILE),
+        format!(r#"{{"settings": {{"version": {}}}}}"#, SETTINGS_VERSION + 1),
+    )
+    .unwrap();
+    assert!(NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime: fake_runtime(),
+    })
+    .is_err());
+}
+
+#[test]
+fn reloads_settings_written_by_another_process() {
+    let (data_dir, manager) = open_manager();
+    modify_settings(&manager, |settings| {
+        settings.app.trash_retention_days = 10;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(get_settings(&manager).unwrap().app.trash_retention_days, 10);
+
+    let mut store = read_store(&data_dir);
+    store["settings"]["app"]["trash_retention_days"] = 100.into();
+    fs::write(
+        data_dir.path().join(STORE_FILE),
+        serde_json::to_vec(&store).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        get_settings(&manager).unwrap().app.trash_retention_days,
+        100
+    );
+}
+
+#[test]
+fn serializes_writes_of_processes_sharing_a_data_directory() {
+    let (data_dir, manager) = open_manager();
+    let other = NodeManager::open(ManagerConfig {
+        data_dir: data_dir.path().to_path_buf(),
+        runtime: fake_runtime(),
+    })
+    .unwrap();
+
+    let writers: Vec<_> = [manager.clone(), other]
+        .into_iter()
+        .map(|manager| {
+            std::thread::spawn(move || {
+                for _ in 0..20 {
+                    modify_settings(&manager, |settings| {
+                        settings.app.trash_retention_days += 1;
+                        Ok(())
+                    })
+                    .unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    let initial = Settings::default().app.trash_retention_days;
+    assert_eq!(
+        read_store(&data_dir)["settings"]["app"]["trash_retention_days"],
+        initial + 40
+    );
+}
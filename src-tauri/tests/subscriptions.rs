@@ -0,0 +1,156 @@
+// Context event subscriptions against a stub WebSocket endpoint listening on the
+// node's server port
+mod common;
+
+use common::{create_test_node, open_manager, record_events, unique_node_name, wait_until};
+use futures_util::{SinkExt, StreamExt};
+use node_multiplatform_tauri::events::ManagerEvent;
+use node_multiplatform_tauri::manager::NodeManager;
+use node_multiplatform_tauri::subscriptions::{
+    get_context_events, list_subscriptions, subscribe_to_contexts, unsubscribe_from_contexts,
+};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream};
+
+type Socket = WebSocketStream<tokio::net::TcpStream>;
+
+// Accept connections on the node's server port and hand them to the test
+async fn start_stub(server_port: u32) -> mpsc::UnboundedReceiver<Socket> {
+    let listener = TcpListener::bind(("127.0.0.1", server_port as u16))
+        .await
+        .unwrap();
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            if let Ok(socket) = accept_async(stream).await {
+                let _ = sender.send(socket);
+            }
+        }
+    });
+    receiver
+}
+
+// Wait for the subscribe request on a new connection and acknowledge it
+async fn accept_subscription(connections: &mut mpsc::UnboundedReceiver<Socket>) -> (Socket, Value) {
+    let mut socket = tokio::time::timeout(Duration::from_secs(10), connections.recv())
+        .await
+        .expect("the relay connects")
+        .unwrap();
+    let Some(Ok(Message::Text(text))) = socket.next().await else {
+        panic!("expected a subscribe request");
+    };
+    let request: Value = serde_json::from_str(&text).unwrap();
+    let response =
+        json!({ "id": request["id"], "result": { "contextIds": request["params"]["contextIds"] } });
+    socket
+        .send(Message::Text(response.to_string()))
+        .await
+        .unwrap();
+    (socket, request)
+}
+
+async fn send_event(socket: &mut Socket, context_id: &str, value: u32) {
+    let event = json!({
+        "id": null,
+        "result": {
+            "contextId": context_id,
+            "type": "ExecutionEvent",
+            "data": { "events": [{ "kind": "Inserted", "data": [value] }] }
+        }
+    });
+    socket.send(Message::Text(event.to_string())).await.unwrap();
+}
+
+async fn wait_for_events(manager: &NodeManager, node_name: &str, count: usize) -> bool {
+    wait_until(Duration::from_secs(10), || async {
+        get_context_events(manager, node_name, None).unwrap().len() >= count
+    })
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn relays_context_events() {
+    let (_data_dir, manager) = open_manager();
+    let published = record_events(&manager, |event| match event {
+        ManagerEvent::ContextEvent { node_name, event } => Some((node_name.clone(), event.clone())),
+        _ => None,
+    });
+    let node_name = unique_node_name("subscribe");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+    let mut connections = start_stub(server_port).await;
+
+    let status = subscribe_to_contexts(
+        &manager,
+        node_name.clone(),
+        vec!["ctx1".to_string(), "ctx2".to_string()],
+    )
+    .unwrap();
+    assert_eq!(status.context_ids, vec!["ctx1", "ctx2"]);
+
+    let (mut socket, request) = accept_subscription(&mut connections).await;
+    assert_eq!(request["method"], "subscribe");
+    assert_eq!(request["params"]["contextIds"], json!(["ctx1", "ctx2"]));
+
+    send_event(&mut socket, "ctx1", 1).await;
+    send_event(&mut socket, "ctx2", 2).await;
+    assert!(wait_for_events(&manager, &node_name, 2).await);
+
+    let events = get_context_events(&manager, &node_name, Some("ctx2")).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type.as_deref(), Some("ExecutionEvent"));
+    assert_eq!(events[0].data["events"][0]["data"], json!([2]));
+
+    let published = published.lock().unwrap();
+    assert_eq!(published.len(), 2);
+    assert!(published.iter().all(|(name, _)| *name == node_name));
+
+    let statuses = list_subscriptions(&manager).unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].connected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reconnects_until_unsubscribed() {
+    let (_data_dir, manager) = open_manager();
+    let node_name = unique_node_name("resubscribe");
+    let (server_port, _) = create_test_node(&manager, &node_name, false).await;
+    let mut connections = start_stub(server_port).await;
+
+    subscribe_to_contexts(&manager, node_name.clone(), vec!["ctx1".to_string()]).unwrap();
+    let (mut socket, _) = accept_subscription(&mut connections).await;
+    send_event(&mut socket, "ctx1", 1).await;
+    assert!(wait_for_events(&manager, &node_name, 1).await);
+
+    // The node drops the connection and the relay subscribes again
+    socket.close(None).await.unwrap();
+    drop(socket);
+    let (mut socket, request) = accept_subscription(&mut connections).await;
+    assert_eq!(request["params"]["contextIds"], json!(["ctx1"]));
+    send_event(&mut socket, "ctx1", 2).await;
+    assert!(wait_for_events(&manager, &node_name, 2).await);
+
+    unsubscribe_from_contexts(&manager, &node_name).unwrap();
+    assert!(list_subscriptions(&manager).unwrap().is_empty());
+    // The relay is stopped, so it does not connect again
+    socket.close(None).await.unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_secs(3), connections.recv())
+            .await
+            .is_err()
+    );
+    // Received events are kept
+    assert_eq!(
+        get_context_events(&manager, &node_name, None)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert!(
+        subscribe_to_contexts(&manager, "missing".to_string(), vec!["ctx1".to_string()]).is_err()
+    );
+    assert!(subscribe_to_contexts(&manager, node_name, Vec::new()).is_err());
+}
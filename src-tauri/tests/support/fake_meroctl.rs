@@ -0,0 +1,183 @@
+// Stand-in for meroctl used by the integration tests. It understands the arguments
+// the manager passes: `init` writes a config.toml with the given ports, `run` binds
+// them, answers every request on the server port with 200 and echoes stdin lines
+// until it reads `exit` or `exit <code>`.
+//
+// Tests script it through the environment of the node runtime:
+// - FAKE_MEROCTL_INIT_EXIT_CODE makes `init` fail with that code
+// - FAKE_MEROCTL_OUTPUT and FAKE_MEROCTL_ERROR_OUTPUT are printed to stdout and
+//   stderr once the node is running
+// - FAKE_MEROCTL_EXIT_CODE is the code a plain `exit` exits with, 0 by default
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::exit;
+use std::thread;
+
+struct Args {
+    node_name: String,
+    home: PathBuf,
+    command: String,
+    server_port: u16,
+    swarm_port: u16,
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        node_name: String::new(),
+        home: PathBuf::new(),
+        command: String::new(),
+        server_port: 0,
+        swarm_port: 0,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--version" => {
+                println!("meroctl 0.0.0-fake");
+                exit(0);
+            }
+            "--node-name" => args.node_name = iter.next().unwrap_or_default(),
+            "--home" => args.home = iter.next().unwrap_or_default().into(),
+            "--server-port" => args.server_port = parse_port(iter.next()),
+            "--swarm-port" => args.swarm_port = parse_port(iter.next()),
+            "init" | "run" if args.command.is_empty() => args.command = arg,
+            _ => {}
+        }
+    }
+    args
+}
+
+fn parse_port(value: Option<String>) -> u16 {
+    value.and_then(|v| v.parse().ok()).unwrap_or_else(|| {
+        eprintln!("invalid port");
+        exit(2)
+    })
+}
+
+// First TCP port in the `listen` addresses of a section of config.toml. The fake is
+// compiled without dependencies, so the config is scanned rather than parsed
+fn read_port(config: &str, section: &str) -> u16 {
+    let header = format!("[{}]", section);
+    config
+        .lines()
+        .skip_while(|line| line.trim() != header)
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .skip_while(|line| !line.trim_start().starts_with("listen"))
+        .find_map(|line| line.split("/tcp/").nth(1))
+        .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|port| port.parse().ok())
+        .unwrap_or_else(|| {
+            eprintln!("missing {} port in config", section);
+            exit(2)
+        })
+}
+
+fn exit_code_from_env(key: &str) -> Option<i32> {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+// Stable per node name, so tests can tell which node an address belongs to
+fn fake_peer_id(node_name: &str) -> String {
+    let name: String = node_name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    format!("12D3KooWFake{}", name)
+}
+
+fn init(args: &Args) {
+    if let Some(code) = exit_code_from_env("FAKE_MEROCTL_INIT_EXIT_CODE") {
+        eprintln!("failed to initialize node {}", args.node_name);
+        exit(code);
+    }
+    let node_dir = args.home.join(&args.node_name);
+    if node_dir.exists() {
+        eprintln!("node {} already exists", args.node_name);
+        exit(1);
+    }
+    fs::create_dir_all(&node_dir).expect("create node directory");
+    let config = format!(
+        "[identity]\npeer_id = \"{peer_id}\"\n\n\
+         [swarm]\nlisten = [\"/ip4/0.0.0.0/tcp/{swarm}\", \"/ip4/0.0.0.0/udp/{swarm}/quic-v1\"]\n\n\
+         [server]\nlisten = [\"/ip4/127.0.0.1/tcp/{server}\"]\n",
+        peer_id = fake_peer_id(&args.node_name),
+        swarm = args.swarm_port,
+        server = args.server_port,
+    );
+    fs::write(node_dir.join("config.toml"), config).expect("write config");
+    println!("Initialized node {}", args.node_name);
+}
+
+fn run(args: &Args) {
+    let config_path = args.home.join(&args.node_name).join("config.toml");
+    let config = fs::read_to_string(&config_path).unwrap_or_else(|_| {
+        eprintln!("failed to read {}", config_path.display());
+        exit(1)
+    });
+
+    let swarm = TcpListener::bind(("127.0.0.1", read_port(&config, "swarm"))).expect("bind swarm");
+    let server =
+        TcpListener::bind(("127.0.0.1", read_port(&config, "server"))).expect("bind server");
+    thread::spawn(move || {
+        for stream in swarm.incoming() {
+            drop(stream);
+        }
+    });
+    thread::spawn(move || {
+        for mut stream in server.incoming().flatten() {
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+        }
+    });
+
+    println!("Node {} is running", args.node_name);
+    if let Ok(output) = env::var("FAKE_MEROCTL_OUTPUT") {
+        println!("{}", output);
+    }
+    if let Ok(output) = env::var("FAKE_MEROCTL_ERROR_OUTPUT") {
+        eprintln!("{}", output);
+    }
+    let _ = io::stdout().flush();
+
+    let default_exit_code = exit_code_from_env("FAKE_MEROCTL_EXIT_CODE").unwrap_or(0);
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let mut words = line.split_whitespace();
+        if words.next() == Some("exit") {
+            let code = words
+                .next()
+                .and_then(|code| code.parse().ok())
+                .unwrap_or(default_exit_code);
+            println!("Node {} is exiting", args.node_name);
+            exit(code);
+        }
+        println!("received: {}", line);
+        let _ = io::stdout().flush();
+    }
+
+    // Keep serving after stdin is closed, like a real node, until killed
+    loop {
+        thread::park();
+    }
+}
+
+fn main() {
+    let args = parse_args();
+    match args.command.as_str() {
+        "init" => init(&args),
+        "run" => run(&args),
+        _ => {
+            eprintln!("unsupported command");
+            exit(2);
+        }
+    }
+}
@@ -1,6 +1,13 @@
 import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 
+export type NodeHealth =
+  | 'stopped'
+  | 'starting'
+  | 'healthy'
+  | 'degraded'
+  | 'unreachable';
+
 export interface NodeDetails {
   name: string;
   is_running: boolean;
@@ -10,8 +17,27 @@ export interface NodeDetails {
     server_port: number;
     swarm_port: number;
   };
+  health: NodeHealth;
+  peer_info: {
+    peer_id: string | null;
+    multiaddrs: string[];
+    dashboard_url: string;
+    jsonrpc_url: string;
+  };
 }
 
+// Payload of the `node-event` event published on node lifecycle changes
+export type NodeEvent =
+  | { type: 'created'; node_name: string }
+  | { type: 'started'; node_name: string }
+  | { type: 'ready'; node_name: string }
+  | { type: 'exited'; node_name: string; exit_code: number | null }
+  | { type: 'stopped'; node_name: string }
+  | { type: 'start_failed'; node_name: string; error: ManagerError }
+  | { type: 'renamed'; previous_name: string; node_name: string }
+  | { type: 'deleted'; node_name: string }
+  | { type: 'config_changed'; node_name: string };
+
 export interface UpdateNodeConfigParams {
   originalNodeName: string;
   nodeName: string;
@@ -24,6 +50,12 @@ export interface CommandResponse {
   success: boolean;
   message: string;
   data: NodeDetails[] | null | string;
+  error?: ManagerError;
+}
+
+export interface ManagerError {
+  code: string;
+  [key: string]: unknown;
 }
 
 export interface NodePorts {
@@ -1,6 +1,6 @@
 import React, { useEffect, useState } from 'react';
 import NodeList from '../../components/node/nodeList';
-import useNodeManagement from '../../hooks/useNodeManagement';
+import useNodeManagement, { NodeEvent } from '../../hooks/useNodeManagement';
 import Button from '../../components/common/button';
 import Header from '../../components/layout/header';
 import PopupWrapper from '../../components/common/popup';
@@ -74,6 +74,11 @@ const Dashboard: React.FC = () => {
           }
         })
       );
+      listeners.push(
+        await listen<NodeEvent>('node-event', () => {
+          refreshNodesList();
+        })
+      );
     };
 
     setupListeners();